@@ -0,0 +1,81 @@
+// times the operations behind the simd feature. run it with and without
+// the feature and compare:
+//
+//   cargo bench --bench kernels
+//   cargo bench --bench kernels --features simd
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use the_ray_tracer_challenege::math::{
+    matrix4::Matrix4, point::Point3, scalar::Scalar, transform::transform, tuple::Tuple,
+    vector::Vector3,
+};
+
+const ITERATIONS: u32 = 20_000_000;
+
+fn time<R>(name: &str, mut operation: impl FnMut(u32) -> R) {
+    // warm up, then keep the best of a few runs
+    for index in 0..ITERATIONS / 10 {
+        black_box(operation(index));
+    }
+
+    let best = (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            for index in 0..ITERATIONS {
+                black_box(operation(index));
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+
+    println!(
+        "{:<32} {:>8.2} ns",
+        name,
+        best.as_nanos() as f64 / ITERATIONS as f64
+    );
+}
+
+fn run<T: Scalar>(label: &str) {
+    let matrix: Matrix4<T> = transform::new_translation(T::ONE, T::TWO, T::ONE)
+        * transform::new_rotation_y(T::from_f64(0.5))
+        * transform::new_scale(T::TWO, T::ONE, T::TWO);
+    let other: Matrix4<T> = transform::new_rotation_x(T::from_f64(0.25));
+    let vector = Vector3::new(T::ONE, T::TWO, T::from_f64(3.0));
+    let point = Point3::new(T::from_f64(-1.0), T::ONE, T::TWO);
+    let tuple = Tuple::new_tuple(T::ONE, T::TWO, T::from_f64(3.0), T::ONE);
+
+    time(&format!("{} Vector3::dot", label), |_| {
+        black_box(&vector).dot(black_box(&vector))
+    });
+    time(&format!("{} Vector3::cross", label), |_| {
+        black_box(&vector).cross(black_box(&vector))
+    });
+    time(&format!("{} Tuple::dot", label), |_| {
+        black_box(&tuple).dot(black_box(&tuple))
+    });
+    time(&format!("{} Matrix4 * Point3", label), |_| {
+        *black_box(&matrix) * *black_box(&point)
+    });
+    time(&format!("{} Matrix4 * Tuple", label), |_| {
+        *black_box(&matrix) * *black_box(&tuple)
+    });
+    time(&format!("{} Matrix4 * Matrix4", label), |_| {
+        *black_box(&matrix) * *black_box(&other)
+    });
+}
+
+fn main() {
+    // cargo bench passes --bench, anything else (cargo test --benches or
+    // --all-targets) only wants to know the bench builds
+    if !std::env::args().any(|arg| arg == "--bench") {
+        return;
+    }
+
+    run::<f64>("f64");
+    run::<f32>("f32");
+}
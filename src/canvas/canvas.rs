@@ -1,48 +1,158 @@
-use std::fs;
-
 use crate::color::color::Color;
 
+use super::error::CanvasError;
+
+// the fields stay visible to the format and image modules, but outside of
+// `canvas` the pixel count can only change through a constructor
 pub struct Canvas {
-    pub width: usize,
-    pub height: usize,
-    pub pixels: Vec<Color>,
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) pixels: Vec<Color>,
 }
 
 impl Canvas {
     pub fn new(width: usize, height: usize) -> Canvas {
+        let Some(size) = width.checked_mul(height) else {
+            panic!("{}", CanvasError::InvalidSize { width, height });
+        };
+
         Canvas {
             width,
             height,
-            pixels: vec![Color::new_color(0.0, 0.0, 0.0); width * height],
+            pixels: vec![Color::new_color(0.0, 0.0, 0.0); size],
+        }
+    }
+
+    pub fn from_pixels(
+        width: usize,
+        height: usize,
+        pixels: Vec<Color>,
+    ) -> Result<Canvas, CanvasError> {
+        if width.checked_mul(height) != Some(pixels.len()) {
+            return Err(CanvasError::InvalidSize { width, height });
         }
+
+        Ok(Canvas {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<Color> {
+        self.pixels
     }
 
     pub fn set_pixel(&mut self, x: usize, y: usize, color: &Color) {
-        assert!(x < self.width);
-        assert!(y < self.height);
-        self.pixels[self.width * y + x] = *color;
+        if let Err(error) = self.try_set(x, y, color) {
+            panic!("{}", error);
+        }
     }
 
     pub fn get_pixel(&self, x: usize, y: usize) -> &Color {
-        assert!(x < self.width);
-        assert!(y < self.height);
-        &self.pixels[self.width * y + x]
+        match self.try_get(x, y) {
+            Some(pixel) => pixel,
+            None => panic!("{}", self.out_of_bounds(x, y)),
+        }
+    }
+
+    pub fn try_get(&self, x: usize, y: usize) -> Option<&Color> {
+        self.index(x, y).map(|index| &self.pixels[index])
+    }
+
+    pub fn try_get_mut(&mut self, x: usize, y: usize) -> Option<&mut Color> {
+        self.index(x, y).map(|index| &mut self.pixels[index])
     }
 
-    pub fn save_to_ppm(&self) {
-        let mut data = format!("P3\n{} {}\n255\n", self.width, self.height);
+    pub fn try_set(&mut self, x: usize, y: usize, color: &Color) -> Result<(), CanvasError> {
+        match self.try_get_mut(x, y) {
+            Some(pixel) => {
+                *pixel = *color;
+                Ok(())
+            }
+            None => Err(self.out_of_bounds(x, y)),
+        }
+    }
 
-        for pixel in self.pixels.iter() {
-            let r = (255.0 * pixel.red()) as u8;
-            let g = (255.0 * pixel.green()) as u8;
-            let b = (255.0 * pixel.blue()) as u8;
+    pub fn row(&self, y: usize) -> Option<&[Color]> {
+        if y < self.height {
+            Some(&self.pixels[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
 
-            data += &format!("{} {} {} ", r, g, b);
+    pub fn row_mut(&mut self, y: usize) -> Option<&mut [Color]> {
+        if y < self.height {
+            Some(&mut self.pixels[y * self.width..(y + 1) * self.width])
+        } else {
+            None
         }
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> + ExactSizeIterator {
+        let width = self.width;
+        (0..self.height).map(move |y| &self.pixels[y * width..(y + 1) * width])
+    }
+
+    // one slice per row like rows, even when the rows are empty
+    pub fn rows_mut(&mut self) -> impl ExactSizeIterator<Item = &mut [Color]> {
+        let width = self.width;
+        let mut rest = self.pixels.as_mut_slice();
+        (0..self.height).map(move |_| {
+            let (row, tail) = std::mem::take(&mut rest).split_at_mut(width);
+            rest = tail;
+            row
+        })
+    }
 
-        data += "\n";
+    pub fn enumerate_pixels(&self) -> impl Iterator<Item = (usize, usize, &Color)> {
+        let width = self.width;
+        self.pixels
+            .iter()
+            .enumerate()
+            .map(move |(index, pixel)| (index % width, index / width, pixel))
+    }
 
-        fs::write("./image.ppm", data).expect("unable to write to file");
+    pub fn enumerate_pixels_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Color)> {
+        let width = self.width;
+        self.pixels
+            .iter_mut()
+            .enumerate()
+            .map(move |(index, pixel)| (index % width, index / width, pixel))
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(self.width * y + x)
+        } else {
+            None
+        }
+    }
+
+    fn out_of_bounds(&self, x: usize, y: usize) -> CanvasError {
+        CanvasError::OutOfBounds {
+            x,
+            y,
+            width: self.width,
+            height: self.height,
+        }
     }
 }
 
@@ -50,7 +160,7 @@ impl Canvas {
 mod tests {
     use std::f64::consts::PI;
 
-    use crate::math::{transform::transform, tuple::Tuple};
+    use crate::math::{point::Point3, transform::transform, vector::Vector3};
 
     use super::*;
 
@@ -58,8 +168,8 @@ mod tests {
     fn create_canvas() {
         let canvas = Canvas::new(100, 100);
         let black = Color::new_color(0.0, 0.0, 0.0);
-        for pixel in canvas.pixels.iter() {
-            assert_eq!(pixel.equals(&black), true);
+        for pixel in canvas.pixels().iter() {
+            assert!(pixel.equals(&black));
         }
     }
 
@@ -69,7 +179,108 @@ mod tests {
         let red = Color::new_color(1.0, 0.0, 0.0);
         canvas.set_pixel(3, 3, &red);
         let pixel = canvas.get_pixel(3, 3);
-        assert_eq!(pixel.equals(&red), true);
+        assert!(pixel.equals(&red));
+    }
+
+    #[test]
+    fn try_get_out_of_bounds() {
+        let canvas = Canvas::new(10, 20);
+        assert!(canvas.try_get(9, 19).is_some());
+        assert!(canvas.try_get(10, 0).is_none());
+        assert!(canvas.try_get(0, 20).is_none());
+    }
+
+    #[test]
+    fn try_set_out_of_bounds() {
+        let mut canvas = Canvas::new(10, 20);
+        let red = Color::new_color(1.0, 0.0, 0.0);
+        assert!(canvas.try_set(9, 19, &red).is_ok());
+        assert!(canvas.get_pixel(9, 19).equals(&red));
+        assert_eq!(
+            canvas.try_set(10, 3, &red),
+            Err(CanvasError::OutOfBounds {
+                x: 10,
+                y: 3,
+                width: 10,
+                height: 20
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_pixel_out_of_bounds() {
+        let canvas = Canvas::new(10, 20);
+        canvas.get_pixel(10, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a valid canvas size")]
+    fn new_size_overflows() {
+        Canvas::new(usize::MAX, 2);
+    }
+
+    #[test]
+    fn from_pixels() {
+        let pixels = vec![Color::new_color(1.0, 1.0, 1.0); 6];
+        let canvas = Canvas::from_pixels(3, 2, pixels).expect("unable to create canvas");
+        assert_eq!(canvas.width(), 3);
+        assert_eq!(canvas.height(), 2);
+
+        let pixels = vec![Color::new_color(1.0, 1.0, 1.0); 5];
+        assert!(Canvas::from_pixels(3, 2, pixels).is_err());
+    }
+
+    #[test]
+    fn rows() {
+        let mut canvas = Canvas::new(3, 2);
+        let red = Color::new_color(1.0, 0.0, 0.0);
+        for pixel in canvas.row_mut(1).expect("missing row") {
+            *pixel = red;
+        }
+
+        let rows: Vec<&[Color]> = canvas.rows().collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].iter().all(|pixel| pixel.red() == 0.0));
+        assert!(rows[1].iter().all(|pixel| pixel.equals(&red)));
+        assert!(canvas.row(2).is_none());
+    }
+
+    #[test]
+    fn rows_mut() {
+        let mut canvas = Canvas::new(2, 3);
+        for (y, row) in canvas.rows_mut().enumerate() {
+            row[0] = Color::new_color(y as f64, 0.0, 0.0);
+        }
+        assert!(canvas
+            .get_pixel(0, 2)
+            .equals(&Color::new_color(2.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn rows_of_zero_width() {
+        let mut canvas = Canvas::new(0, 3);
+        assert_eq!(canvas.rows().len(), 3);
+        assert_eq!(canvas.rows_mut().len(), 3);
+        assert!(canvas.rows_mut().all(|row| row.is_empty()));
+    }
+
+    #[test]
+    fn enumerate_pixels() {
+        let mut canvas = Canvas::new(3, 2);
+        for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+            *pixel = Color::new_color(x as f64, y as f64, 0.0);
+        }
+
+        let positions: Vec<(usize, usize)> =
+            canvas.enumerate_pixels().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(
+            positions,
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+        assert!(canvas
+            .get_pixel(2, 1)
+            .equals(&Color::new_color(2.0, 1.0, 0.0)));
     }
 
     #[test]
@@ -77,25 +288,26 @@ mod tests {
         let mut canvas = Canvas::new(1000, 1000);
         let red = Color::new_color(1.0, 0.0, 0.0);
 
-        let mut projectile = Tuple::new_point(0.0, 999.0, 0.0);
-        let mut velocity = Tuple::new_vector(3.0, -12.0, 0.0);
-        let gravity = Tuple::new_vector(0.0, 0.1, 0.0);
+        let mut projectile = Point3::new(0.0, 999.0, 0.0);
+        let mut velocity = Vector3::new(3.0, -12.0, 0.0);
+        let gravity = Vector3::new(0.0, 0.1, 0.0);
 
         for _ in 0..250 {
             let x = projectile.x as usize;
             let y = projectile.y as usize;
 
-            if x >= canvas.width || y >= canvas.height {
+            if canvas.try_set(x, y, &red).is_err() {
                 break;
             }
 
-            canvas.set_pixel(x, y, &red);
-
             projectile += velocity;
             velocity += gravity;
         }
         canvas.set_pixel(3, 3, &red);
-        canvas.save_to_ppm();
+
+        let mut buffer = vec![];
+        canvas.write_ppm(&mut buffer).expect("unable to write ppm");
+        assert!(buffer.starts_with(b"P3\n1000 1000\n255\n"));
     }
 
     #[test]
@@ -103,20 +315,22 @@ mod tests {
         let mut canvas = Canvas::new(100, 100);
         let mut color = Color::new_color(1.0, 1.0, 0.5);
 
-        let mut dot = Tuple::new_point(25.0, 0.0, 0.0);
+        let mut dot = Point3::new(25.0, 0.0, 0.0);
 
         let offset = transform::new_translation(50.0, 50.0, 0.0);
         let rotate = transform::new_rotation_z(PI / 32.0);
 
         for _ in 0..100 {
-            dot = &rotate * dot;
-            let with_offset = &offset * dot;
+            dot = rotate * dot;
+            let with_offset = offset * dot;
             let x = with_offset.x as usize;
             let y = with_offset.y as usize;
             canvas.set_pixel(x, y, &color);
-            color = &rotate * color;
+            color *= 0.98;
         }
 
-        canvas.save_to_ppm();
+        let mut buffer = vec![];
+        canvas.write_ppm(&mut buffer).expect("unable to write ppm");
+        assert!(buffer.starts_with(b"P3\n100 100\n255\n"));
     }
 }
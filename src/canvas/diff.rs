@@ -0,0 +1,247 @@
+use crate::color::color::Color;
+
+use super::{canvas::Canvas, error::CanvasError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub pixels: usize,
+    pub mismatched: usize,
+    pub first_mismatch: Option<(usize, usize)>,
+    pub max_difference: f64,
+    pub rmse: f64,
+}
+
+impl Comparison {
+    pub fn matches(&self) -> bool {
+        self.mismatched == 0
+    }
+
+    // peak signal to noise ratio in decibels for colors in the 0 to 1 range,
+    // infinite when the canvases are identical
+    pub fn psnr(&self) -> f64 {
+        if self.rmse == 0.0 {
+            f64::INFINITY
+        } else {
+            20.0 * (1.0 / self.rmse).log10()
+        }
+    }
+}
+
+impl Canvas {
+    pub fn compare(&self, other: &Canvas, tolerance: f64) -> Result<Comparison, CanvasError> {
+        self.check_same_size(other)?;
+
+        let mut comparison = Comparison {
+            pixels: self.pixels.len(),
+            mismatched: 0,
+            first_mismatch: None,
+            max_difference: 0.0,
+            rmse: 0.0,
+        };
+
+        let mut squared_error = 0.0;
+        for ((x, y, a), b) in self.enumerate_pixels().zip(other.pixels.iter()) {
+            let difference = channel_differences(a, b);
+            let largest = largest_difference(&difference);
+
+            squared_error += difference.iter().map(|value| value * value).sum::<f64>();
+            comparison.max_difference = largest_difference(&[comparison.max_difference, largest]);
+
+            if largest.is_nan() || largest > tolerance {
+                comparison.mismatched += 1;
+                comparison.first_mismatch.get_or_insert((x, y));
+            }
+        }
+
+        if !self.pixels.is_empty() {
+            comparison.rmse = (squared_error / (self.pixels.len() * 3) as f64).sqrt();
+        }
+
+        Ok(comparison)
+    }
+
+    pub fn rmse(&self, other: &Canvas) -> Result<f64, CanvasError> {
+        Ok(self.compare(other, f64::INFINITY)?.rmse)
+    }
+
+    pub fn psnr(&self, other: &Canvas) -> Result<f64, CanvasError> {
+        Ok(self.compare(other, f64::INFINITY)?.psnr())
+    }
+
+    // mismatched pixels become magenta over a dimmed grayscale copy of self
+    pub fn diff(&self, other: &Canvas, tolerance: f64) -> Result<Canvas, CanvasError> {
+        self.check_same_size(other)?;
+
+        let highlight = Color::new_color(1.0, 0.0, 1.0);
+        let pixels = self
+            .pixels
+            .iter()
+            .zip(other.pixels.iter())
+            .map(|(a, b)| {
+                let largest = largest_difference(&channel_differences(a, b));
+                if largest.is_nan() || largest > tolerance {
+                    highlight
+                } else {
+                    let luminance = 0.2126 * a.red() + 0.7152 * a.green() + 0.0722 * a.blue();
+                    let gray = luminance.clamp(0.0, 1.0) * 0.25;
+                    Color::new_color(gray, gray, gray)
+                }
+            })
+            .collect();
+
+        Canvas::from_pixels(self.width, self.height, pixels)
+    }
+
+    fn check_same_size(&self, other: &Canvas) -> Result<(), CanvasError> {
+        if self.width != other.width || self.height != other.height {
+            return Err(CanvasError::SizeMismatch {
+                expected: (self.width, self.height),
+                actual: (other.width, other.height),
+            });
+        }
+        Ok(())
+    }
+}
+
+// unlike f64::max this keeps a nan, so pixels that can't be compared
+// count as mismatches rather than disappearing
+fn largest_difference(differences: &[f64]) -> f64 {
+    differences.iter().fold(0.0, |largest, &value| {
+        if value > largest || value.is_nan() {
+            value
+        } else {
+            largest
+        }
+    })
+}
+
+fn channel_differences(a: &Color, b: &Color) -> [f64; 3] {
+    [
+        (a.red() - b.red()).abs(),
+        (a.green() - b.green()).abs(),
+        (a.blue() - b.blue()).abs(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::compare::equal;
+
+    use super::*;
+
+    fn gradient() -> Canvas {
+        let mut canvas = Canvas::new(4, 3);
+        for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+            *pixel = Color::new_color(x as f64 / 4.0, y as f64 / 3.0, 0.5);
+        }
+        canvas
+    }
+
+    #[test]
+    fn compare_identical() {
+        let a = gradient();
+        let b = gradient();
+        let comparison = a.compare(&b, 0.0).expect("unable to compare");
+
+        assert!(comparison.matches());
+        assert_eq!(comparison.pixels, 12);
+        assert_eq!(comparison.first_mismatch, None);
+        assert_eq!(comparison.rmse, 0.0);
+        assert_eq!(comparison.psnr(), f64::INFINITY);
+    }
+
+    #[test]
+    fn compare_within_tolerance() {
+        let a = gradient();
+        let mut b = gradient();
+        b.set_pixel(
+            1,
+            1,
+            &(*a.get_pixel(1, 1) + Color::new_color(0.0, 0.0, 0.01)),
+        );
+
+        let comparison = a.compare(&b, 0.02).expect("unable to compare");
+        assert!(comparison.matches());
+        assert!(equal(comparison.max_difference, 0.01));
+        assert!(comparison.rmse > 0.0);
+    }
+
+    #[test]
+    fn compare_reports_mismatches() {
+        let a = gradient();
+        let mut b = gradient();
+        b.set_pixel(2, 1, &Color::new_color(1.0, 1.0, 1.0));
+        b.set_pixel(3, 2, &Color::new_color(0.0, 0.0, 0.0));
+
+        let comparison = a.compare(&b, 0.01).expect("unable to compare");
+        assert!(!comparison.matches());
+        assert_eq!(comparison.mismatched, 2);
+        assert_eq!(comparison.first_mismatch, Some((2, 1)));
+    }
+
+    #[test]
+    fn compare_nan_is_a_mismatch() {
+        let a = gradient();
+        let mut b = gradient();
+        b.set_pixel(1, 2, &Color::new_color(f64::NAN, 0.5, 0.5));
+
+        let comparison = a.compare(&b, 0.01).expect("unable to compare");
+        assert!(!comparison.matches());
+        assert_eq!(comparison.mismatched, 1);
+        assert_eq!(comparison.first_mismatch, Some((1, 2)));
+        assert!(comparison.max_difference.is_nan());
+        assert!(comparison.rmse.is_nan());
+
+        let comparison = b.compare(&b, 0.01).expect("unable to compare");
+        assert!(!comparison.matches());
+
+        let diff = a.diff(&b, 0.01).expect("unable to diff");
+        assert!(diff
+            .get_pixel(1, 2)
+            .equals(&Color::new_color(1.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn rmse_and_psnr() {
+        let a = Canvas::new(2, 2);
+        let mut b = Canvas::new(2, 2);
+        for pixel in b.pixels_mut() {
+            *pixel = Color::new_color(0.1, 0.1, 0.1);
+        }
+
+        assert!(equal(a.rmse(&b).expect("unable to compare"), 0.1));
+        assert!(equal(a.psnr(&b).expect("unable to compare"), 20.0));
+    }
+
+    #[test]
+    fn compare_size_mismatch() {
+        let a = Canvas::new(2, 2);
+        let b = Canvas::new(3, 2);
+        assert_eq!(
+            a.compare(&b, 0.0),
+            Err(CanvasError::SizeMismatch {
+                expected: (2, 2),
+                actual: (3, 2)
+            })
+        );
+        assert!(a.diff(&b, 0.0).is_err());
+    }
+
+    #[test]
+    fn diff_highlights_mismatches() {
+        let a = gradient();
+        let mut b = gradient();
+        b.set_pixel(0, 2, &Color::new_color(1.0, 0.0, 0.0));
+
+        let diff = a.diff(&b, 0.01).expect("unable to diff");
+        assert_eq!(diff.width(), 4);
+        assert_eq!(diff.height(), 3);
+        assert!(diff
+            .get_pixel(0, 2)
+            .equals(&Color::new_color(1.0, 0.0, 1.0)));
+
+        let unchanged = diff.get_pixel(1, 1);
+        assert!(unchanged.red() < 0.25);
+        assert_eq!(unchanged.red(), unchanged.blue());
+    }
+}
@@ -0,0 +1,571 @@
+use crate::color::color::Color;
+
+use super::canvas::Canvas;
+
+// Drawing clips to the canvas, so shapes may start or end outside of it.
+impl Canvas {
+    // steps along the longer axis and rounds the other one, which picks the
+    // same pixels as Bresenham's midpoint test. the pixel for any step can be
+    // worked out directly, so only the steps inside the canvas are visited
+    pub fn draw_line(&mut self, x0: isize, y0: isize, x1: isize, y1: isize, color: &Color) {
+        let dx = x1 as i128 - x0 as i128;
+        let dy = y1 as i128 - y0 as i128;
+        let steep = dy.abs() > dx.abs();
+        let (major, minor, major_delta, minor_delta, major_len, minor_len) = if steep {
+            (y0 as i128, x0 as i128, dy, dx, self.height, self.width)
+        } else {
+            (x0 as i128, y0 as i128, dx, dy, self.width, self.height)
+        };
+        let (major_step, minor_step) = (major_delta.signum(), minor_delta.signum());
+        let length = major_delta.unsigned_abs();
+        let rise = minor_delta.unsigned_abs();
+
+        // how far the minor axis has moved after step steps, rounded half up
+        let offset = |step: u128| {
+            if length == 0 {
+                return 0;
+            }
+            let product = step * (rise % length);
+            let remainder = product % length;
+            step * (rise / length) + product / length + u128::from(2 * remainder >= length)
+        };
+
+        // the steps that keep each axis inside the canvas, as offsets from
+        // the start of the line
+        let Some((first, last)) = step_range(major, major_step, major_len, length) else {
+            return;
+        };
+        let Some((low, high)) = step_range(minor, minor_step, minor_len, rise) else {
+            return;
+        };
+        // offset only grows, so search for the steps where it enters and
+        // leaves low..=high
+        let first = first.max(partition(length, |step| offset(step) < low));
+        let end = (last + 1).min(partition(length, |step| offset(step) <= high));
+
+        for step in first..end {
+            let x = major + major_step * step as i128;
+            let y = minor + minor_step * offset(step) as i128;
+            let (x, y) = if steep { (y, x) } else { (x, y) };
+            self.plot(x as isize, y as isize, color);
+        }
+    }
+
+    pub fn draw_line_aa(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, color: &Color) {
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        let (x0, y0, x1, y1) = if steep {
+            (y0, x0, y1, x1)
+        } else {
+            (x0, y0, x1, y1)
+        };
+        let (x0, y0, x1, y1) = if x0 > x1 {
+            (x1, y1, x0, y0)
+        } else {
+            (x0, y0, x1, y1)
+        };
+
+        let (major_len, minor_len) = if steep {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        // skip lines that can't reach the canvas, so the column loop below
+        // only ever walks visible columns
+        if ![x0, y0, x1, y1].iter().all(|value| value.is_finite())
+            || x1 < -1.0
+            || x0 > major_len as f64 + 1.0
+            || y0.max(y1) < -1.0
+            || y0.min(y1) > minor_len as f64 + 1.0
+        {
+            return;
+        }
+
+        let dx = x1 - x0;
+        let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+        let plot = |canvas: &mut Canvas, x: f64, y: f64, coverage: f64| {
+            let (x, y) = if steep { (y, x) } else { (x, y) };
+            canvas.blend(x as isize, y as isize, color, coverage);
+        };
+
+        // the end points only cover part of their pixel column
+        let start_x = x0.round();
+        let start_y = y0 + gradient * (start_x - x0);
+        let start_gap = 1.0 - fpart(x0 + 0.5);
+        plot(
+            self,
+            start_x,
+            start_y.floor(),
+            (1.0 - fpart(start_y)) * start_gap,
+        );
+        plot(
+            self,
+            start_x,
+            start_y.floor() + 1.0,
+            fpart(start_y) * start_gap,
+        );
+
+        let end_x = x1.round();
+        let end_y = y1 + gradient * (end_x - x1);
+        let end_gap = fpart(x1 + 0.5);
+        plot(self, end_x, end_y.floor(), (1.0 - fpart(end_y)) * end_gap);
+        plot(self, end_x, end_y.floor() + 1.0, fpart(end_y) * end_gap);
+
+        let first = (start_x + 1.0).max(0.0) as usize;
+        let last = end_x.min(major_len as f64).max(0.0) as usize;
+        for column in first..last {
+            let x = column as f64;
+            let y = start_y + gradient * (x - start_x);
+            plot(self, x, y.floor(), 1.0 - fpart(y));
+            plot(self, x, y.floor() + 1.0, fpart(y));
+        }
+    }
+
+    pub fn draw_circle(&mut self, center_x: isize, center_y: isize, radius: isize, color: &Color) {
+        let radius = radius as i128;
+        let center_x = center_x as i128;
+        let center_y = center_y as i128;
+        if radius < 0
+            || center_x + radius < 0
+            || center_x - radius >= self.width as i128
+            || center_y + radius < 0
+            || center_y - radius >= self.height as i128
+        {
+            return;
+        }
+
+        // every point of the outline is offset from the center by (x, y) or
+        // (y, x) for some step y of the first octant, so only the steps that
+        // line up with a row or column of the canvas can land on it
+        let mut steps: Vec<i128> = (0..self.height as i128)
+            .map(|row| (row - center_y).abs())
+            .chain((0..self.width as i128).map(|column| (column - center_x).abs()))
+            .filter(|&step| step <= radius)
+            .collect();
+        steps.sort_unstable();
+        steps.dedup();
+
+        let squared = (radius * radius) as u128;
+        for y in steps {
+            // the midpoint test picks the largest x with x * x - x < r * r - y * y
+            let target = squared - (y * y) as u128;
+            let x = partition(radius as u128, |x| x == 0 || x * x - x < target) as i128 - 1;
+            if x < y {
+                continue;
+            }
+
+            for (px, py) in [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ] {
+                self.plot(saturate(center_x + px), saturate(center_y + py), color);
+            }
+        }
+    }
+
+    pub fn fill_circle(&mut self, center_x: isize, center_y: isize, radius: isize, color: &Color) {
+        // i128 keeps radius * radius and the offsets from overflowing
+        let radius = radius as i128;
+        let center_x = center_x as i128;
+        let center_y = center_y as i128;
+        let first = (-radius).max(-center_y);
+        let last = radius.min(self.height as i128 - 1 - center_y);
+
+        for y in first..=last {
+            let half_width = ((radius * radius - y * y) as f64).sqrt().round() as i128;
+            self.draw_span(
+                saturate(center_x - half_width),
+                saturate(center_x + half_width),
+                saturate(center_y + y),
+                color,
+            );
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: isize, y: isize, width: usize, height: usize, color: &Color) {
+        let width = isize::try_from(width).unwrap_or(isize::MAX);
+        let height = isize::try_from(height).unwrap_or(isize::MAX);
+        let end_x = x.saturating_add(width).saturating_sub(1);
+        let end_y = y.saturating_add(height).min(self.height as isize);
+
+        for row in y.max(0)..end_y {
+            self.draw_span(x, end_x, row, color);
+        }
+    }
+
+    // even-odd fill of the pixels whose centers are inside the polygon
+    pub fn fill_polygon(&mut self, points: &[(f64, f64)], color: &Color) {
+        if points.len() < 3 {
+            return;
+        }
+
+        let min_y = points.iter().map(|point| point.1).fold(f64::MAX, f64::min);
+        let max_y = points.iter().map(|point| point.1).fold(f64::MIN, f64::max);
+        let first_row = (min_y.floor() as isize).max(0);
+        let last_row = (max_y.ceil() as isize).min(self.height as isize - 1);
+
+        let mut crossings = vec![];
+        for row in first_row..=last_row {
+            let center_y = row as f64 + 0.5;
+
+            crossings.clear();
+            for (index, &(x0, y0)) in points.iter().enumerate() {
+                let (x1, y1) = points[(index + 1) % points.len()];
+                if (y0 <= center_y) != (y1 <= center_y) {
+                    crossings.push(x0 + (center_y - y0) / (y1 - y0) * (x1 - x0));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            for pair in crossings.chunks_exact(2) {
+                let start = (pair[0] - 0.5).ceil() as isize;
+                let end = (pair[1] - 0.5).ceil() as isize - 1;
+                self.draw_span(start, end, row, color);
+            }
+        }
+    }
+
+    fn plot(&mut self, x: isize, y: isize, color: &Color) {
+        if x >= 0 && y >= 0 {
+            let _ = self.try_set(x as usize, y as usize, color);
+        }
+    }
+
+    fn blend(&mut self, x: isize, y: isize, color: &Color, alpha: f64) {
+        if x < 0 || y < 0 {
+            return;
+        }
+
+        if let Some(pixel) = self.try_get_mut(x as usize, y as usize) {
+            let alpha = alpha.clamp(0.0, 1.0);
+            *pixel = Color::new_color(
+                pixel.red() + (color.red() - pixel.red()) * alpha,
+                pixel.green() + (color.green() - pixel.green()) * alpha,
+                pixel.blue() + (color.blue() - pixel.blue()) * alpha,
+            );
+        }
+    }
+
+    fn draw_span(&mut self, start: isize, end: isize, y: isize, color: &Color) {
+        if y < 0 || y >= self.height as isize {
+            return;
+        }
+
+        let start = start.max(0);
+        let end = end.min(self.width as isize - 1);
+        if start > end {
+            return;
+        }
+
+        let row = self.row_mut(y as usize).expect("row is inside the canvas");
+        row[start as usize..=end as usize].fill(*color);
+    }
+}
+
+// the steps in 0..=length for which start + direction * step stays within
+// 0..size, or None if there are none
+fn step_range(start: i128, direction: i128, size: usize, length: u128) -> Option<(u128, u128)> {
+    let size = size as i128;
+    let (low, high) = match direction {
+        0 if (0..size).contains(&start) => return Some((0, length)),
+        0 => return None,
+        1 => (-start, size - 1 - start),
+        _ => (start - (size - 1), start),
+    };
+    if high < 0 || low > length as i128 {
+        return None;
+    }
+
+    Some((low.max(0) as u128, (high as u128).min(length)))
+}
+
+// the first value in 0..=length for which predicate is false, given that
+// predicate is true for every value before it
+fn partition(length: u128, predicate: impl Fn(u128) -> bool) -> u128 {
+    let (mut low, mut high) = (0, length + 1);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if predicate(middle) {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+fn saturate(value: i128) -> isize {
+    value.clamp(isize::MIN as i128, isize::MAX as i128) as isize
+}
+
+// unlike f64::fract this stays positive for negative values, so lines
+// above or left of the canvas still get the right coverage
+fn fpart(value: f64) -> f64 {
+    value - value.floor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(canvas: &Canvas) -> Vec<(usize, usize)> {
+        canvas
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.red() > 0.0)
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    fn red() -> Color {
+        Color::new_color(1.0, 0.0, 0.0)
+    }
+
+    #[test]
+    fn line_horizontal() {
+        let mut canvas = Canvas::new(5, 3);
+        canvas.draw_line(1, 1, 3, 1, &red());
+        assert_eq!(lit(&canvas), vec![(1, 1), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn line_diagonal_reversed() {
+        let mut canvas = Canvas::new(4, 4);
+        canvas.draw_line(3, 3, 0, 0, &red());
+        assert_eq!(lit(&canvas), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn line_shallow() {
+        let mut canvas = Canvas::new(5, 2);
+        canvas.draw_line(0, 0, 4, 1, &red());
+        let pixels = lit(&canvas);
+        assert_eq!(pixels.len(), 5);
+        assert_eq!(pixels.first(), Some(&(0, 0)));
+        assert!(pixels.contains(&(4, 1)));
+    }
+
+    #[test]
+    fn line_clips() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_line(-2, 1, 5, 1, &red());
+        assert_eq!(lit(&canvas), vec![(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn line_aa_horizontal_is_solid() {
+        let mut canvas = Canvas::new(6, 3);
+        canvas.draw_line_aa(1.0, 1.0, 4.0, 1.0, &red());
+        for x in 1..=4 {
+            assert!(canvas.get_pixel(x, 1).red() > 0.49);
+        }
+        assert!(lit(&canvas).iter().all(|&(_, y)| y == 1));
+    }
+
+    #[test]
+    fn line_aa_splits_coverage() {
+        let mut canvas = Canvas::new(6, 4);
+        canvas.draw_line_aa(0.0, 1.5, 5.0, 1.5, &red());
+        let top = canvas.get_pixel(2, 1).red();
+        let bottom = canvas.get_pixel(2, 2).red();
+        assert!((top - 0.5).abs() < 0.0001);
+        assert!((bottom - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn line_aa_straddles_top_edge() {
+        let mut canvas = Canvas::new(6, 3);
+        canvas.draw_line_aa(0.0, -0.3, 5.0, -0.3, &red());
+        for x in 1..5 {
+            assert!((canvas.get_pixel(x, 0).red() - 0.7).abs() < 0.0001);
+        }
+        assert!(lit(&canvas).iter().all(|&(_, y)| y == 0));
+    }
+
+    #[test]
+    fn line_aa_steep() {
+        let mut canvas = Canvas::new(3, 6);
+        canvas.draw_line_aa(1.0, 0.0, 1.0, 5.0, &red());
+        for y in 1..5 {
+            assert!(canvas.get_pixel(1, y).equals(&red()));
+        }
+    }
+
+    #[test]
+    fn circle() {
+        let mut canvas = Canvas::new(11, 11);
+        canvas.draw_circle(5, 5, 3, &red());
+        let pixels = lit(&canvas);
+        for (x, y) in [(8, 5), (2, 5), (5, 8), (5, 2)] {
+            assert!(pixels.contains(&(x, y)));
+        }
+        assert!(!pixels.contains(&(5, 5)));
+        for (x, y) in pixels {
+            let distance = ((x as f64 - 5.0).powi(2) + (y as f64 - 5.0).powi(2)).sqrt();
+            assert!((distance - 3.0).abs() < 1.0);
+        }
+    }
+
+    #[test]
+    fn filled_circle() {
+        let mut canvas = Canvas::new(11, 11);
+        canvas.fill_circle(5, 5, 3, &red());
+        let pixels = lit(&canvas);
+        assert!(pixels.contains(&(5, 5)));
+        assert!(pixels.contains(&(8, 5)));
+        assert!(!pixels.contains(&(8, 8)));
+    }
+
+    #[test]
+    fn fill_rect() {
+        let mut canvas = Canvas::new(5, 5);
+        canvas.fill_rect(1, 2, 2, 3, &red());
+        assert_eq!(
+            lit(&canvas),
+            vec![(1, 2), (2, 2), (1, 3), (2, 3), (1, 4), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn fill_rect_clips() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.fill_rect(-1, -1, 10, 2, &red());
+        assert_eq!(lit(&canvas), vec![(0, 0), (1, 0), (2, 0)]);
+    }
+
+    #[test]
+    fn fill_polygon_square() {
+        let mut canvas = Canvas::new(6, 6);
+        canvas.fill_polygon(&[(1.0, 1.0), (4.0, 1.0), (4.0, 3.0), (1.0, 3.0)], &red());
+        assert_eq!(
+            lit(&canvas),
+            vec![(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn fill_polygon_triangle() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.fill_polygon(&[(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)], &red());
+        let pixels = lit(&canvas);
+        assert!(pixels.contains(&(0, 0)));
+        assert!(pixels.contains(&(8, 0)));
+        assert!(pixels.contains(&(0, 8)));
+        assert!(!pixels.contains(&(5, 5)));
+        assert!(!pixels.contains(&(9, 9)));
+    }
+
+    #[test]
+    fn fill_polygon_needs_three_points() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.fill_polygon(&[(0.0, 0.0), (3.0, 3.0)], &red());
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn line_aa_huge_coordinates() {
+        let mut canvas = Canvas::new(4, 3);
+        canvas.draw_line_aa(0.0, 0.0, 1e17, 1.0, &red());
+        assert!(canvas.get_pixel(3, 0).red() > 0.99);
+    }
+
+    #[test]
+    fn line_aa_off_canvas() {
+        let mut canvas = Canvas::new(4, 3);
+        canvas.draw_line_aa(-1e12, -5.0, 1e12, -5.0, &red());
+        canvas.draw_line_aa(10.0, 1.0, 1e9, 2.0, &red());
+        canvas.draw_line_aa(0.0, f64::NAN, 3.0, 1.0, &red());
+        canvas.draw_line_aa(0.0, 1.0, f64::INFINITY, 1.0, &red());
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn filled_circle_off_canvas() {
+        let mut canvas = Canvas::new(5, 5);
+        canvas.fill_circle(-20, 2, 3, &red());
+        canvas.fill_circle(2, 100, 3, &red());
+        canvas.fill_circle(2, 2, -1, &red());
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn filled_circle_extreme() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.fill_circle(1, 1, 4_000_000_000, &red());
+        assert_eq!(lit(&canvas).len(), 9);
+
+        let mut canvas = Canvas::new(3, 3);
+        canvas.fill_circle(isize::MAX, isize::MIN, isize::MAX, &red());
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn fill_rect_extreme() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.fill_rect(isize::MAX - 1, 0, 10, 10, &red());
+        canvas.fill_rect(0, isize::MAX - 1, 10, 10, &red());
+        assert!(lit(&canvas).is_empty());
+
+        canvas.fill_rect(isize::MIN, isize::MIN, usize::MAX, usize::MAX, &red());
+        assert!(lit(&canvas).is_empty());
+
+        canvas.fill_rect(-5, -5, usize::MAX, usize::MAX, &red());
+        assert_eq!(lit(&canvas).len(), 9);
+    }
+
+    #[test]
+    fn line_extreme() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_line(isize::MIN, 0, 2, 0, &red());
+        assert_eq!(lit(&canvas), vec![(0, 0), (1, 0), (2, 0)]);
+
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_line(isize::MIN, isize::MIN, isize::MAX, isize::MAX, &red());
+        assert_eq!(lit(&canvas), vec![(0, 0), (1, 1), (2, 2)]);
+
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_line(1, isize::MAX, 1, isize::MIN, &red());
+        assert_eq!(lit(&canvas), vec![(1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn line_off_canvas() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_line(-40_000_000, -5, 40_000_000, -5, &red());
+        canvas.draw_line(5, isize::MIN, 5, isize::MAX, &red());
+        canvas.draw_line(isize::MAX, isize::MAX, isize::MAX - 1, isize::MAX, &red());
+        assert!(lit(&canvas).is_empty());
+    }
+
+    #[test]
+    fn line_matches_unclipped_pixels() {
+        let mut canvas = Canvas::new(20, 10);
+        canvas.draw_line(-7, -3, 25, 12, &red());
+        let mut expect = Canvas::new(40, 30);
+        expect.draw_line(3, 7, 35, 22, &red());
+        let expect: Vec<_> = lit(&expect)
+            .into_iter()
+            .filter(|&(x, y)| (10..30).contains(&x) && (10..20).contains(&y))
+            .map(|(x, y)| (x - 10, y - 10))
+            .collect();
+        assert_eq!(lit(&canvas), expect);
+    }
+
+    #[test]
+    fn circle_extreme() {
+        let mut canvas = Canvas::new(3, 3);
+        canvas.draw_circle(1, 1, isize::MIN, &red());
+        canvas.draw_circle(1, 1, 20_000_000, &red());
+        canvas.draw_circle(isize::MAX, isize::MIN, isize::MAX, &red());
+        canvas.draw_circle(-100, 1, 10, &red());
+        assert!(lit(&canvas).is_empty());
+
+        canvas.draw_circle(-1_000_000_000, 1, 1_000_000_001, &red());
+        assert_eq!(lit(&canvas), vec![(1, 0), (1, 1), (1, 2)]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    UnsupportedFormat(String),
+    InvalidHeader(String),
+    InvalidValue(String),
+    UnexpectedEof,
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::Io(error) => write!(f, "io error: {}", error),
+            ImageError::UnsupportedFormat(magic) => write!(f, "unsupported format: {}", magic),
+            ImageError::InvalidHeader(message) => write!(f, "invalid header: {}", message),
+            ImageError::InvalidValue(value) => write!(f, "invalid value: {}", value),
+            ImageError::UnexpectedEof => write!(f, "unexpected end of file"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImageError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ImageError {
+    fn from(error: io::Error) -> Self {
+        ImageError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanvasError {
+    OutOfBounds {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    InvalidSize {
+        width: usize,
+        height: usize,
+    },
+    SizeMismatch {
+        expected: (usize, usize),
+        actual: (usize, usize),
+    },
+}
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanvasError::OutOfBounds {
+                x,
+                y,
+                width,
+                height,
+            } => write!(
+                f,
+                "({}, {}) is outside of the {}x{} canvas",
+                x, y, width, height
+            ),
+            CanvasError::InvalidSize { width, height } => {
+                write!(f, "{}x{} is not a valid canvas size", width, height)
+            }
+            CanvasError::SizeMismatch { expected, actual } => write!(
+                f,
+                "expected a {}x{} canvas, got {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CanvasError {}
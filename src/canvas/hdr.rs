@@ -0,0 +1,477 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::color::color::Color;
+
+use super::{canvas::Canvas, error::ImageError};
+
+const MIN_RUN_LENGTH: usize = 4;
+const MAX_RUN_LENGTH: usize = 127;
+const MAX_DUMP_LENGTH: usize = 128;
+
+impl Canvas {
+    pub fn save_to_hdr<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_hdr(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn write_hdr<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(
+            writer,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            self.height, self.width
+        )?;
+
+        let mut data = vec![];
+        for row in self.rows() {
+            let scanline: Vec<[u8; 4]> = row.iter().map(to_rgbe).collect();
+
+            if (8..=0x7fff).contains(&self.width) {
+                data.extend_from_slice(&[2, 2, (self.width >> 8) as u8, self.width as u8]);
+                for channel in 0..4 {
+                    let bytes: Vec<u8> = scanline.iter().map(|rgbe| rgbe[channel]).collect();
+                    write_run_length(&mut data, &bytes);
+                }
+            } else {
+                data.extend(scanline.iter().flatten());
+            }
+        }
+
+        writer.write_all(&data)
+    }
+
+    pub fn load_hdr<P: AsRef<Path>>(path: P) -> Result<Canvas, ImageError> {
+        Canvas::parse_hdr(&fs::read(path)?)
+    }
+
+    pub fn read_hdr<R: Read>(reader: &mut R) -> Result<Canvas, ImageError> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        Canvas::parse_hdr(&data)
+    }
+
+    pub fn parse_hdr(data: &[u8]) -> Result<Canvas, ImageError> {
+        let mut parser = HdrParser { data, position: 0 };
+
+        let magic = parser.line()?;
+        if !magic.starts_with("#?") {
+            return Err(ImageError::UnsupportedFormat(magic));
+        }
+
+        loop {
+            let line = parser.line()?;
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(format) = line.strip_prefix("FORMAT=") {
+                if format != "32-bit_rle_rgbe" {
+                    return Err(ImageError::UnsupportedFormat(String::from(format)));
+                }
+            }
+        }
+
+        let resolution = parser.line()?;
+        let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+            ["-Y", height, "+X", width] => (
+                height
+                    .parse::<usize>()
+                    .map_err(|_| ImageError::InvalidHeader(resolution.clone()))?,
+                width
+                    .parse::<usize>()
+                    .map_err(|_| ImageError::InvalidHeader(resolution.clone()))?,
+            ),
+            _ => return Err(ImageError::UnsupportedFormat(resolution)),
+        };
+
+        let too_large = || ImageError::InvalidHeader(format!("{}x{} is too large", width, height));
+        width.checked_mul(height).ok_or_else(too_large)?;
+
+        // an empty image has no scanline data to read at all
+        if width == 0 || height == 0 {
+            return Canvas::from_pixels(width, height, vec![])
+                .map_err(|error| ImageError::InvalidHeader(error.to_string()));
+        }
+
+        // every scanline needs at least its 4 byte run length header, or all
+        // of its pixels when it can't be run length encoded. the pixels are
+        // still collected as they decode rather than sized from the header
+        let scanline_length = if (8..=0x7fff).contains(&width) {
+            Some(4)
+        } else {
+            width.checked_mul(4)
+        };
+        let length = scanline_length
+            .and_then(|length| length.checked_mul(height))
+            .ok_or_else(too_large)?;
+        if length > parser.remaining() {
+            return Err(ImageError::UnexpectedEof);
+        }
+
+        let mut pixels = vec![];
+        for _ in 0..height {
+            let scanline = parser.scanline(width)?;
+            pixels.extend(scanline.iter().map(from_rgbe));
+        }
+
+        Canvas::from_pixels(width, height, pixels)
+            .map_err(|error| ImageError::InvalidHeader(error.to_string()))
+    }
+}
+
+pub fn to_rgbe(color: &Color) -> [u8; 4] {
+    let red = color.red().max(0.0);
+    let green = color.green().max(0.0);
+    let blue = color.blue().max(0.0);
+    let value = red.max(green).max(blue);
+
+    if !value.is_finite() || value < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    let (mantissa, exponent) = frexp(value);
+    if exponent > 127 {
+        return [255, 255, 255, 255];
+    }
+
+    let scale = mantissa * 256.0 / value;
+    [
+        (red * scale) as u8,
+        (green * scale) as u8,
+        (blue * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+pub fn from_rgbe(rgbe: &[u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::new_color(0.0, 0.0, 0.0);
+    }
+
+    let scale = 2.0_f64.powi(rgbe[3] as i32 - (128 + 8));
+    Color::new_color(
+        rgbe[0] as f64 * scale,
+        rgbe[1] as f64 * scale,
+        rgbe[2] as f64 * scale,
+    )
+}
+
+// splits value into a mantissa in [0.5, 1) and a power of two
+fn frexp(value: f64) -> (f64, i32) {
+    let mut exponent = value.log2().floor() as i32 + 1;
+    let mut mantissa = value / 2.0_f64.powi(exponent);
+
+    if mantissa >= 1.0 {
+        mantissa /= 2.0;
+        exponent += 1;
+    } else if mantissa < 0.5 {
+        mantissa *= 2.0;
+        exponent -= 1;
+    }
+
+    (mantissa, exponent)
+}
+
+fn write_run_length(output: &mut Vec<u8>, data: &[u8]) {
+    let mut current = 0;
+
+    while current < data.len() {
+        let mut run_start = current;
+        let mut run_length = 0;
+        let mut previous_run_length = 0;
+
+        while run_length < MIN_RUN_LENGTH && run_start < data.len() {
+            run_start += run_length;
+            previous_run_length = run_length;
+            run_length = 1;
+            while run_start + run_length < data.len()
+                && run_length < MAX_RUN_LENGTH
+                && data[run_start] == data[run_start + run_length]
+            {
+                run_length += 1;
+            }
+        }
+
+        // a short run right at the start is cheaper as a run than as a dump
+        if previous_run_length > 1 && previous_run_length == run_start - current {
+            output.push((128 + previous_run_length) as u8);
+            output.push(data[current]);
+            current = run_start;
+        }
+
+        while current < run_start {
+            let length = (run_start - current).min(MAX_DUMP_LENGTH);
+            output.push(length as u8);
+            output.extend_from_slice(&data[current..current + length]);
+            current += length;
+        }
+
+        if run_length >= MIN_RUN_LENGTH {
+            output.push((128 + run_length) as u8);
+            output.push(data[run_start]);
+            current += run_length;
+        }
+    }
+}
+
+struct HdrParser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> HdrParser<'a> {
+    fn line(&mut self) -> Result<String, ImageError> {
+        let rest = &self.data[self.position..];
+        let end = rest
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or(ImageError::UnexpectedEof)?;
+        self.position += end + 1;
+
+        Ok(String::from_utf8_lossy(&rest[..end])
+            .trim_end_matches('\r')
+            .to_string())
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    fn bytes(&mut self, length: usize) -> Result<&'a [u8], ImageError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + length)
+            .ok_or(ImageError::UnexpectedEof)?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8, ImageError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn scanline(&mut self, width: usize) -> Result<Vec<[u8; 4]>, ImageError> {
+        let header = self
+            .data
+            .get(self.position..self.position + 4)
+            .ok_or(ImageError::UnexpectedEof)?;
+
+        if !(8..=0x7fff).contains(&width)
+            || header[0] != 2
+            || header[1] != 2
+            || header[2] & 0x80 != 0
+        {
+            if header[0] == 1 && header[1] == 1 && header[2] == 1 {
+                return Err(ImageError::UnsupportedFormat(String::from(
+                    "old style run length encoding",
+                )));
+            }
+
+            return Ok(self
+                .bytes(width * 4)?
+                .chunks(4)
+                .map(|rgbe| [rgbe[0], rgbe[1], rgbe[2], rgbe[3]])
+                .collect());
+        }
+
+        let encoded_width = (header[2] as usize) << 8 | header[3] as usize;
+        if encoded_width != width {
+            return Err(ImageError::InvalidValue(format!(
+                "scanline width {} does not match image width {}",
+                encoded_width, width
+            )));
+        }
+        self.position += 4;
+
+        let mut scanline = vec![[0; 4]; width];
+        for channel in 0..4 {
+            let mut x = 0;
+            while x < width {
+                let count = self.byte()? as usize;
+                let (length, run) = if count > 128 {
+                    (count - 128, true)
+                } else {
+                    (count, false)
+                };
+
+                if length == 0 || x + length > width {
+                    return Err(ImageError::InvalidValue(String::from(
+                        "bad scanline run length",
+                    )));
+                }
+
+                if run {
+                    let value = self.byte()?;
+                    for rgbe in scanline[x..x + length].iter_mut() {
+                        rgbe[channel] = value;
+                    }
+                } else {
+                    for (rgbe, &value) in
+                        scanline[x..x + length].iter_mut().zip(self.bytes(length)?)
+                    {
+                        rgbe[channel] = value;
+                    }
+                }
+
+                x += length;
+            }
+        }
+
+        Ok(scanline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::temp_file::TempFile;
+    use super::*;
+
+    fn close(a: &Color, b: &Color) -> bool {
+        let tolerance = a.red().max(a.green()).max(a.blue()) / 128.0;
+        (a.red() - b.red()).abs() <= tolerance
+            && (a.green() - b.green()).abs() <= tolerance
+            && (a.blue() - b.blue()).abs() <= tolerance
+    }
+
+    #[test]
+    fn rgbe_encoding() {
+        assert_eq!(
+            to_rgbe(&Color::new_color(1.0, 1.0, 1.0)),
+            [128, 128, 128, 129]
+        );
+        assert_eq!(to_rgbe(&Color::new_color(0.0, 0.0, 0.0)), [0, 0, 0, 0]);
+        assert_eq!(to_rgbe(&Color::new_color(-1.0, 0.5, 0.0)), [0, 128, 0, 128]);
+    }
+
+    #[test]
+    fn rgbe_decoding() {
+        let color = from_rgbe(&[128, 64, 0, 129]);
+        assert!(color.equals(&Color::new_color(1.0, 0.5, 0.0)));
+        assert!(from_rgbe(&[12, 34, 56, 0]).equals(&Color::new_color(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn rgbe_keeps_high_dynamic_range() {
+        for color in [
+            Color::new_color(1000.0, 0.5, 2.0),
+            Color::new_color(0.001, 0.002, 0.0005),
+            Color::new_color(3.75, 3.75, 3.75),
+        ] {
+            assert!(close(&color, &from_rgbe(&to_rgbe(&color))));
+        }
+    }
+
+    #[test]
+    fn run_length_encoding() {
+        let mut output = vec![];
+        write_run_length(&mut output, &[5, 5, 5, 5, 5, 1, 2, 3]);
+        assert_eq!(output, vec![133, 5, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn write_hdr_header() {
+        let canvas = Canvas::new(3, 2);
+        let mut buffer = vec![];
+        canvas.write_hdr(&mut buffer).expect("unable to write hdr");
+
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 3\n";
+        assert!(buffer.starts_with(header));
+        assert_eq!(buffer.len(), header.len() + 3 * 2 * 4);
+    }
+
+    #[test]
+    fn round_trip_flat() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.set_pixel(0, 0, &Color::new_color(12.5, 0.25, 0.0));
+        canvas.set_pixel(2, 1, &Color::new_color(0.1, 0.2, 0.3));
+
+        let mut buffer = vec![];
+        canvas.write_hdr(&mut buffer).expect("unable to write hdr");
+        let result = Canvas::read_hdr(&mut buffer.as_slice()).expect("unable to read hdr");
+
+        assert_eq!(result.width, 3);
+        assert_eq!(result.height, 2);
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(close(a, b));
+        }
+    }
+
+    #[test]
+    fn round_trip_run_length() {
+        let mut canvas = Canvas::new(300, 4);
+        for y in 0..4 {
+            for x in 0..300 {
+                let value = (x / 7) as f64 * 0.5;
+                canvas.set_pixel(x, y, &Color::new_color(value, 1.0, 100.0 - value));
+            }
+        }
+
+        let file = TempFile::new("round_trip_run_length.hdr");
+        canvas.save_to_hdr(file.path()).expect("unable to save hdr");
+        let result = Canvas::load_hdr(file.path()).expect("unable to load hdr");
+
+        assert_eq!(result.width, 300);
+        assert_eq!(result.height, 4);
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(close(a, b));
+        }
+        assert!(std::fs::metadata(file.path()).unwrap().len() < 300 * 4 * 4);
+    }
+
+    #[test]
+    fn round_trip_zero_sized() {
+        for (width, height) in [(0, 3), (3, 0), (0, 0)] {
+            let mut buffer = vec![];
+            Canvas::new(width, height)
+                .write_hdr(&mut buffer)
+                .expect("unable to write hdr");
+            let result = Canvas::read_hdr(&mut buffer.as_slice()).expect("unable to read hdr");
+
+            assert_eq!(result.width, width);
+            assert_eq!(result.height, height);
+        }
+    }
+
+    #[test]
+    fn parse_unsupported_format() {
+        let result =
+            Canvas::parse_hdr(b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n\0\0\0\0");
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn parse_unsupported_orientation() {
+        let result = Canvas::parse_hdr(b"#?RADIANCE\n\n+Y 1 +X 1\n\0\0\0\0");
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn parse_header_larger_than_data() {
+        let result = Canvas::parse_hdr(b"#?RADIANCE\n\n-Y 200000 +X 200000\n\x02\x02\x00\x08");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+
+        let result = Canvas::parse_hdr(b"#?RADIANCE\n\n-Y 200000 +X 4\n\x00\x00\x00\x00");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+
+        // enough bytes for the headers, but the runs can't fill the scanlines
+        let mut data = b"#?RADIANCE\n\n-Y 100000 +X 30000\n".to_vec();
+        data.extend_from_slice(&[2, 2, 0x75, 0x30].repeat(100000));
+        assert!(Canvas::parse_hdr(&data).is_err());
+
+        // nothing to read, so the height must not drive a loop
+        let canvas = Canvas::parse_hdr(b"#?RADIANCE\n\n-Y 18446744073709551615 +X 0\n")
+            .expect("unable to parse empty hdr");
+        assert_eq!(canvas.width, 0);
+        assert_eq!(canvas.height, usize::MAX);
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let result = Canvas::parse_hdr(b"#?RADIANCE\n\n-Y 2 +X 2\n\0\0\0\0");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+    }
+}
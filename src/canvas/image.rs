@@ -0,0 +1,336 @@
+use crate::color::color::Color;
+
+use super::{canvas::Canvas, error::CanvasError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeFilter {
+    Nearest,
+    Bilinear,
+}
+
+impl Canvas {
+    pub fn crop(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<Canvas, CanvasError> {
+        self.check_region(x, y, width, height)?;
+
+        let mut result = Canvas::new(width, height);
+        for row in 0..height {
+            let start = self.width * (y + row) + x;
+            result.pixels[row * width..(row + 1) * width]
+                .copy_from_slice(&self.pixels[start..start + width]);
+        }
+
+        Ok(result)
+    }
+
+    pub fn blit(&mut self, source: &Canvas, x: usize, y: usize) -> Result<(), CanvasError> {
+        self.check_region(x, y, source.width, source.height)?;
+
+        for row in 0..source.height {
+            let start = self.width * (y + row) + x;
+            self.pixels[start..start + source.width]
+                .copy_from_slice(&source.pixels[row * source.width..(row + 1) * source.width]);
+        }
+
+        Ok(())
+    }
+
+    pub fn resize(
+        &self,
+        width: usize,
+        height: usize,
+        filter: ResizeFilter,
+    ) -> Result<Canvas, CanvasError> {
+        // a pixel count or byte size that overflows would panic in Canvas::new
+        let fits = width
+            .checked_mul(height)
+            .and_then(|size| size.checked_mul(std::mem::size_of::<Color>()))
+            .is_some_and(|bytes| bytes <= isize::MAX as usize);
+        if width == 0 || height == 0 || !fits {
+            return Err(CanvasError::InvalidSize { width, height });
+        }
+        if self.width == 0 || self.height == 0 {
+            return Err(CanvasError::InvalidSize {
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        let scale_x = self.width as f64 / width as f64;
+        let scale_y = self.height as f64 / height as f64;
+
+        let mut result = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                // sample at pixel centers so both directions stay symmetric
+                let source_x = (x as f64 + 0.5) * scale_x - 0.5;
+                let source_y = (y as f64 + 0.5) * scale_y - 0.5;
+
+                result.pixels[y * width + x] = match filter {
+                    ResizeFilter::Nearest => self.nearest(source_x, source_y),
+                    ResizeFilter::Bilinear => self.bilinear(source_x, source_y),
+                };
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn flip_horizontal(&mut self) {
+        for row in self.pixels.chunks_mut(self.width.max(1)) {
+            row.reverse();
+        }
+    }
+
+    pub fn flip_vertical(&mut self) {
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.pixels.split_at_mut((self.height - 1 - y) * self.width);
+            top[y * self.width..(y + 1) * self.width].swap_with_slice(&mut bottom[..self.width]);
+        }
+    }
+
+    fn check_region(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<(), CanvasError> {
+        let right = x.checked_add(width);
+        let bottom = y.checked_add(height);
+
+        match (right, bottom) {
+            (Some(right), Some(bottom)) if right <= self.width && bottom <= self.height => Ok(()),
+            _ => Err(CanvasError::OutOfBounds {
+                x: x.saturating_add(width.saturating_sub(1)),
+                y: y.saturating_add(height.saturating_sub(1)),
+                width: self.width,
+                height: self.height,
+            }),
+        }
+    }
+
+    fn nearest(&self, x: f64, y: f64) -> Color {
+        let x = (x.round().max(0.0) as usize).min(self.width - 1);
+        let y = (y.round().max(0.0) as usize).min(self.height - 1);
+        self.pixels[y * self.width + x]
+    }
+
+    fn bilinear(&self, x: f64, y: f64) -> Color {
+        let x = x.clamp(0.0, (self.width - 1) as f64);
+        let y = y.clamp(0.0, (self.height - 1) as f64);
+
+        let x0 = x.floor() as usize;
+        let y0 = y.floor() as usize;
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+        let tx = x - x0 as f64;
+        let ty = y - y0 as f64;
+
+        let top = lerp(
+            &self.pixels[y0 * self.width + x0],
+            &self.pixels[y0 * self.width + x1],
+            tx,
+        );
+        let bottom = lerp(
+            &self.pixels[y1 * self.width + x0],
+            &self.pixels[y1 * self.width + x1],
+            tx,
+        );
+        lerp(&top, &bottom, ty)
+    }
+}
+
+fn lerp(a: &Color, b: &Color, t: f64) -> Color {
+    Color::new_color(
+        a.red() + (b.red() - a.red()) * t,
+        a.green() + (b.green() - a.green()) * t,
+        a.blue() + (b.blue() - a.blue()) * t,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered(width: usize, height: usize) -> Canvas {
+        let mut canvas = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                canvas.set_pixel(x, y, &Color::new_color(x as f64, y as f64, 0.0));
+            }
+        }
+        canvas
+    }
+
+    #[test]
+    fn crop() {
+        let canvas = numbered(5, 4);
+        let result = canvas.crop(1, 2, 3, 2).expect("unable to crop");
+
+        assert_eq!(result.width, 3);
+        assert_eq!(result.height, 2);
+        assert!(result
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(1.0, 2.0, 0.0)));
+        assert!(result
+            .get_pixel(2, 1)
+            .equals(&Color::new_color(3.0, 3.0, 0.0)));
+    }
+
+    #[test]
+    fn crop_out_of_bounds() {
+        let canvas = numbered(5, 4);
+        let result = canvas.crop(3, 0, 3, 2);
+        assert_eq!(
+            result.err(),
+            Some(CanvasError::OutOfBounds {
+                x: 5,
+                y: 1,
+                width: 5,
+                height: 4
+            })
+        );
+        assert!(canvas.crop(usize::MAX, 0, 2, 1).is_err());
+    }
+
+    #[test]
+    fn blit() {
+        let mut canvas = Canvas::new(4, 4);
+        let tile = numbered(2, 2);
+        canvas.blit(&tile, 2, 1).expect("unable to blit");
+
+        assert!(canvas
+            .get_pixel(2, 1)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+        assert!(canvas
+            .get_pixel(3, 2)
+            .equals(&Color::new_color(1.0, 1.0, 0.0)));
+        assert!(canvas
+            .get_pixel(1, 1)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn blit_out_of_bounds() {
+        let mut canvas = Canvas::new(4, 4);
+        let tile = numbered(2, 2);
+        assert!(canvas.blit(&tile, 3, 0).is_err());
+        assert!(canvas.blit(&tile, 0, 3).is_err());
+    }
+
+    #[test]
+    fn crop_then_blit_round_trip() {
+        let canvas = numbered(6, 6);
+        let mut result = Canvas::new(6, 6);
+        for (x, y) in [(0, 0), (3, 0), (0, 3), (3, 3)] {
+            let tile = canvas.crop(x, y, 3, 3).expect("unable to crop");
+            result.blit(&tile, x, y).expect("unable to blit");
+        }
+
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(a.equals(b));
+        }
+    }
+
+    #[test]
+    fn resize_nearest() {
+        let canvas = numbered(2, 2);
+        let result = canvas
+            .resize(4, 4, ResizeFilter::Nearest)
+            .expect("unable to resize");
+
+        assert_eq!(result.width, 4);
+        assert_eq!(result.height, 4);
+        assert!(result
+            .get_pixel(1, 1)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+        assert!(result
+            .get_pixel(2, 1)
+            .equals(&Color::new_color(1.0, 0.0, 0.0)));
+        assert!(result
+            .get_pixel(3, 3)
+            .equals(&Color::new_color(1.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn resize_bilinear() {
+        let canvas = numbered(2, 1);
+        let result = canvas
+            .resize(4, 1, ResizeFilter::Bilinear)
+            .expect("unable to resize");
+
+        let red: Vec<f64> = result.pixels.iter().map(|pixel| pixel.red()).collect();
+        assert_eq!(red, vec![0.0, 0.25, 0.75, 1.0]);
+    }
+
+    #[test]
+    fn resize_bilinear_downscale_averages() {
+        let mut canvas = Canvas::new(2, 2);
+        canvas.set_pixel(0, 0, &Color::new_color(1.0, 1.0, 1.0));
+        let result = canvas
+            .resize(1, 1, ResizeFilter::Bilinear)
+            .expect("unable to resize");
+
+        assert!(result
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(0.25, 0.25, 0.25)));
+    }
+
+    #[test]
+    fn resize_invalid_size() {
+        let canvas = numbered(2, 2);
+        assert_eq!(
+            canvas.resize(0, 3, ResizeFilter::Nearest).err(),
+            Some(CanvasError::InvalidSize {
+                width: 0,
+                height: 3
+            })
+        );
+        assert!(Canvas::new(0, 0)
+            .resize(2, 2, ResizeFilter::Bilinear)
+            .is_err());
+        assert_eq!(
+            canvas.resize(usize::MAX, 2, ResizeFilter::Nearest).err(),
+            Some(CanvasError::InvalidSize {
+                width: usize::MAX,
+                height: 2
+            })
+        );
+        assert!(canvas
+            .resize(usize::MAX / 2, 1, ResizeFilter::Bilinear)
+            .is_err());
+    }
+
+    #[test]
+    fn flip_horizontal() {
+        let mut canvas = numbered(3, 2);
+        canvas.flip_horizontal();
+        assert!(canvas
+            .get_pixel(0, 1)
+            .equals(&Color::new_color(2.0, 1.0, 0.0)));
+        assert!(canvas
+            .get_pixel(2, 0)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn flip_vertical() {
+        let mut canvas = numbered(2, 3);
+        canvas.flip_vertical();
+        assert!(canvas
+            .get_pixel(1, 0)
+            .equals(&Color::new_color(1.0, 2.0, 0.0)));
+        assert!(canvas
+            .get_pixel(0, 1)
+            .equals(&Color::new_color(0.0, 1.0, 0.0)));
+        assert!(canvas
+            .get_pixel(0, 2)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+    }
+}
@@ -1 +1,12 @@
 pub mod canvas;
+pub mod diff;
+pub mod draw;
+pub mod error;
+pub mod hdr;
+pub mod image;
+pub mod pfm;
+pub mod png;
+pub mod ppm;
+#[cfg(test)]
+mod temp_file;
+pub mod tone;
@@ -0,0 +1,195 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::color::color::Color;
+
+use super::{canvas::Canvas, error::ImageError};
+
+impl Canvas {
+    pub fn save_to_pfm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_pfm(&mut writer)?;
+        writer.flush()
+    }
+
+    // a negative scale marks the samples as little endian
+    pub fn write_pfm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "PF\n{} {}\n-1.0\n", self.width, self.height)?;
+
+        let mut data = Vec::with_capacity(self.pixels.len() * 12);
+        for row in self.rows().rev() {
+            for pixel in row.iter() {
+                for value in [pixel.red(), pixel.green(), pixel.blue()] {
+                    data.extend_from_slice(&(value as f32).to_le_bytes());
+                }
+            }
+        }
+
+        writer.write_all(&data)
+    }
+
+    pub fn load_pfm<P: AsRef<Path>>(path: P) -> Result<Canvas, ImageError> {
+        Canvas::parse_pfm(&fs::read(path)?)
+    }
+
+    pub fn read_pfm<R: Read>(reader: &mut R) -> Result<Canvas, ImageError> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        Canvas::parse_pfm(&data)
+    }
+
+    pub fn parse_pfm(data: &[u8]) -> Result<Canvas, ImageError> {
+        let mut position = 0;
+        let mut tokens = vec![];
+
+        while tokens.len() < 4 {
+            while data.get(position).is_some_and(u8::is_ascii_whitespace) {
+                position += 1;
+            }
+
+            let start = position;
+            while data
+                .get(position)
+                .is_some_and(|byte| !byte.is_ascii_whitespace())
+            {
+                position += 1;
+            }
+
+            if start == position {
+                return Err(ImageError::UnexpectedEof);
+            }
+            tokens.push(String::from_utf8_lossy(&data[start..position]).into_owned());
+        }
+
+        // exactly one whitespace character separates the header from the raster
+        position += 1;
+
+        let channels = match tokens[0].as_str() {
+            "PF" => 3,
+            "Pf" => 1,
+            _ => return Err(ImageError::UnsupportedFormat(tokens[0].clone())),
+        };
+
+        let width: usize = tokens[1]
+            .parse()
+            .map_err(|_| ImageError::InvalidHeader(tokens[1].clone()))?;
+        let height: usize = tokens[2]
+            .parse()
+            .map_err(|_| ImageError::InvalidHeader(tokens[2].clone()))?;
+        let scale: f64 = tokens[3]
+            .parse()
+            .map_err(|_| ImageError::InvalidHeader(tokens[3].clone()))?;
+
+        if scale == 0.0 || !scale.is_finite() {
+            return Err(ImageError::InvalidHeader(tokens[3].clone()));
+        }
+        let little_endian = scale < 0.0;
+
+        let size = width
+            .checked_mul(height)
+            .and_then(|size| size.checked_mul(channels * 4))
+            .ok_or_else(|| {
+                ImageError::InvalidHeader(format!("{}x{} is too large", width, height))
+            })?;
+
+        let raster = data
+            .get(position..)
+            .and_then(|raster| raster.get(..size))
+            .ok_or(ImageError::UnexpectedEof)?;
+
+        let samples: Vec<f64> = raster
+            .chunks(4)
+            .map(|bytes| {
+                let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+                if little_endian {
+                    f32::from_le_bytes(bytes) as f64
+                } else {
+                    f32::from_be_bytes(bytes) as f64
+                }
+            })
+            .collect();
+
+        let mut canvas = Canvas::new(width, height);
+        for (index, pixel) in samples.chunks(channels).enumerate() {
+            let x = index % width;
+            let y = height - 1 - index / width;
+
+            canvas.pixels[y * width + x] = if channels == 1 {
+                Color::new_color(pixel[0], pixel[0], pixel[0])
+            } else {
+                Color::new_color(pixel[0], pixel[1], pixel[2])
+            };
+        }
+
+        Ok(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::temp_file::TempFile;
+    use super::*;
+
+    #[test]
+    fn write_pfm_bottom_to_top() {
+        let mut canvas = Canvas::new(1, 2);
+        canvas.set_pixel(0, 0, &Color::new_color(1.0, 2.0, 3.0));
+        canvas.set_pixel(0, 1, &Color::new_color(4.0, 5.0, 6.0));
+
+        let mut buffer = vec![];
+        canvas.write_pfm(&mut buffer).expect("unable to write pfm");
+
+        let mut expect = b"PF\n1 2\n-1.0\n".to_vec();
+        for value in [4.0_f32, 5.0, 6.0, 1.0, 2.0, 3.0] {
+            expect.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(buffer, expect);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut canvas = Canvas::new(5, 3);
+        canvas.set_pixel(0, 0, &Color::new_color(1000.0, -0.5, 0.125));
+        canvas.set_pixel(4, 2, &Color::new_color(0.1, 0.2, 0.3));
+
+        let file = TempFile::new("round_trip.pfm");
+        canvas.save_to_pfm(file.path()).expect("unable to save pfm");
+        let result = Canvas::load_pfm(file.path()).expect("unable to load pfm");
+
+        assert_eq!(result.width, 5);
+        assert_eq!(result.height, 3);
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(a.equals(b));
+        }
+    }
+
+    #[test]
+    fn parse_big_endian_grayscale() {
+        let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+        data.extend_from_slice(&0.5_f32.to_be_bytes());
+        data.extend_from_slice(&8.0_f32.to_be_bytes());
+
+        let canvas = Canvas::read_pfm(&mut data.as_slice()).expect("unable to read pfm");
+        assert!(canvas
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(0.5, 0.5, 0.5)));
+        assert!(canvas
+            .get_pixel(1, 0)
+            .equals(&Color::new_color(8.0, 8.0, 8.0)));
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let result = Canvas::parse_pfm(b"PF\n2 2\n-1.0\n\0\0\0\0");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn parse_unsupported_format() {
+        let result = Canvas::parse_pfm(b"P6\n2 2\n255\n");
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat(_))));
+    }
+}
@@ -0,0 +1,594 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use super::{canvas::Canvas, tone::ColorEncoding};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitDepth {
+    Eight,
+    Sixteen,
+}
+
+impl Canvas {
+    pub fn save_to_png<P: AsRef<Path>>(&self, path: P, bit_depth: BitDepth) -> io::Result<()> {
+        self.save_to_png_with(path, bit_depth, &ColorEncoding::default())
+    }
+
+    pub fn save_to_png_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        bit_depth: BitDepth,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_png_with(&mut writer, bit_depth, encoding)?;
+        writer.flush()
+    }
+
+    pub fn write_png<W: Write>(&self, writer: &mut W, bit_depth: BitDepth) -> io::Result<()> {
+        self.write_png_with(writer, bit_depth, &ColorEncoding::default())
+    }
+
+    pub fn write_png_with<W: Write>(
+        &self,
+        writer: &mut W,
+        bit_depth: BitDepth,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        let width = png_dimension(self.width)?;
+        let height = png_dimension(self.height)?;
+
+        let mut header = vec![];
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        header.push(match bit_depth {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen => 16,
+        });
+        // color type 2 (rgb), deflate compression, adaptive filtering, no interlace
+        header.extend_from_slice(&[2, 0, 0, 0]);
+
+        let mut scanlines = vec![];
+        for row in self.rows() {
+            scanlines.push(0);
+            for pixel in row.iter() {
+                for value in [pixel.red(), pixel.green(), pixel.blue()] {
+                    match bit_depth {
+                        BitDepth::Eight => scanlines.push(encoding.to_byte(value)),
+                        BitDepth::Sixteen => {
+                            scanlines.extend_from_slice(&encoding.to_word(value).to_be_bytes())
+                        }
+                    }
+                }
+            }
+        }
+
+        writer.write_all(&PNG_SIGNATURE)?;
+        write_chunk(writer, b"IHDR", &header)?;
+        write_chunk(writer, b"IDAT", &zlib_compress(&scanlines))?;
+        write_chunk(writer, b"IEND", &[])
+    }
+}
+
+fn png_dimension(value: usize) -> io::Result<u32> {
+    match u32::try_from(value) {
+        Ok(value) if value > 0 && value < 1 << 31 => Ok(value),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a valid png dimension", value),
+        )),
+    }
+}
+
+fn write_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "png chunk is too large"))?;
+
+    let mut crc = Crc32::new();
+    crc.update(kind);
+    crc.update(data);
+
+    writer.write_all(&length.to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    writer.write_all(&crc.finish().to_be_bytes())
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut value = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                0xedb88320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[index] = value;
+        index += 1;
+    }
+    table
+}
+
+pub struct Crc32 {
+    value: u32,
+}
+
+impl Crc32 {
+    pub fn new() -> Crc32 {
+        Crc32 { value: 0xffffffff }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.value =
+                CRC32_TABLE[((self.value ^ byte as u32) & 0xff) as usize] ^ (self.value >> 8);
+        }
+    }
+
+    pub fn finish(&self) -> u32 {
+        self.value ^ 0xffffffff
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Crc32::new()
+    }
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    let mut a = 1;
+    let mut b = 0;
+
+    // 5552 is the largest block that cannot overflow before the modulo
+    for block in data.chunks(5552) {
+        for &byte in block {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+
+    (b << 16) | a
+}
+
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    // 32k window, deflate, no preset dictionary
+    let mut output = vec![0x78, 0x01];
+    output.extend_from_slice(&deflate(data));
+    output.extend_from_slice(&adler32(data).to_be_bytes());
+    output
+}
+
+const WINDOW_SIZE: usize = 1 << 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+// A single final block using the fixed huffman codes from RFC 1951 with
+// greedy hash chain matching.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::new();
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW_SIZE];
+
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &head, &previous);
+
+        let advance = if length >= MIN_MATCH {
+            write_length(&mut bits, length);
+            write_distance(&mut bits, distance);
+            length
+        } else {
+            write_literal(&mut bits, data[position] as u16);
+            1
+        };
+
+        for index in position..position + advance {
+            if index + MIN_MATCH <= data.len() {
+                let hash = hash(&data[index..]);
+                previous[index % WINDOW_SIZE] = head[hash];
+                head[hash] = index;
+            }
+        }
+        position += advance;
+    }
+
+    write_literal(&mut bits, 256);
+    bits.finish()
+}
+
+fn hash(data: &[u8]) -> usize {
+    let value = (data[0] as usize) << 16 | (data[1] as usize) << 8 | data[2] as usize;
+    (value.wrapping_mul(2654435761) >> 8) & ((1 << HASH_BITS) - 1)
+}
+
+fn longest_match(
+    data: &[u8],
+    position: usize,
+    head: &[usize],
+    previous: &[usize],
+) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let max_length = MAX_MATCH.min(data.len() - position);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[position..])];
+
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW_SIZE - 1 {
+            break;
+        }
+
+        let length = data[candidate..]
+            .iter()
+            .zip(data[position..position + max_length].iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+
+        let next = previous[candidate % WINDOW_SIZE];
+        if next == usize::MAX || next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+
+    best
+}
+
+fn write_literal(bits: &mut BitWriter, value: u16) {
+    let (code, length) = match value {
+        0..=143 => (0x30 + value, 8),
+        144..=255 => (0x190 + value - 144, 9),
+        256..=279 => (value - 256, 7),
+        _ => (0xc0 + value - 280, 8),
+    };
+    bits.write_huffman(code as u32, length);
+}
+
+fn write_length(bits: &mut BitWriter, length: usize) {
+    let index = LENGTH_BASE
+        .iter()
+        .rposition(|&base| base as usize <= length)
+        .expect("match length is below the minimum");
+    write_literal(bits, 257 + index as u16);
+    bits.write(
+        (length - LENGTH_BASE[index] as usize) as u32,
+        LENGTH_EXTRA[index],
+    );
+}
+
+fn write_distance(bits: &mut BitWriter, distance: usize) {
+    let index = DISTANCE_BASE
+        .iter()
+        .rposition(|&base| base as usize <= distance)
+        .expect("match distance is below the minimum");
+    bits.write_huffman(index as u32, 5);
+    bits.write(
+        (distance - DISTANCE_BASE[index] as usize) as u32,
+        DISTANCE_EXTRA[index],
+    );
+}
+
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u32,
+    count: u8,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            output: vec![],
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn write(&mut self, value: u32, length: u8) {
+        for bit in 0..length {
+            self.buffer |= ((value >> bit) & 1) << self.count;
+            self.count += 1;
+            if self.count == 8 {
+                self.output.push(self.buffer as u8);
+                self.buffer = 0;
+                self.count = 0;
+            }
+        }
+    }
+
+    // huffman codes are packed starting from their most significant bit
+    fn write_huffman(&mut self, code: u32, length: u8) {
+        for bit in (0..length).rev() {
+            self.write((code >> bit) & 1, 1);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.output.push(self.buffer as u8);
+        }
+        self.output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::color::Color;
+
+    use super::super::temp_file::TempFile;
+    use super::*;
+
+    fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut result = vec![];
+        let mut position = PNG_SIGNATURE.len();
+        while position < png.len() {
+            let length = u32::from_be_bytes(png[position..position + 4].try_into().unwrap());
+            let kind = &png[position + 4..position + 8];
+            let data = &png[position + 8..position + 8 + length as usize];
+            let crc = u32::from_be_bytes(
+                png[position + 8 + length as usize..position + 12 + length as usize]
+                    .try_into()
+                    .unwrap(),
+            );
+
+            let mut expect = Crc32::new();
+            expect.update(kind);
+            expect.update(data);
+            assert_eq!(crc, expect.finish());
+
+            result.push((String::from_utf8(kind.to_vec()).unwrap(), data.to_vec()));
+            position += 12 + length as usize;
+        }
+        result
+    }
+
+    // huffman codes are read starting from their most significant bit
+    fn huffman(bit: &mut impl FnMut(u8) -> u32, count: u8) -> u32 {
+        (0..count).fold(0, |code, _| (code << 1) | bit(1))
+    }
+
+    // just enough of RFC 1951 to read back what deflate writes, a single
+    // block with the fixed huffman codes
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut position = 0;
+        let mut bit = |count: u8| {
+            let mut value = 0;
+            for index in 0..count {
+                let byte = data[position / 8];
+                value |= (((byte >> (position % 8)) & 1) as u32) << index;
+                position += 1;
+            }
+            value
+        };
+
+        assert_eq!(bit(1), 1, "expected the final block");
+        assert_eq!(bit(2), 1, "expected fixed huffman codes");
+
+        let mut output: Vec<u8> = vec![];
+        loop {
+            let mut code = huffman(&mut bit, 7);
+            let symbol = if code <= 0b0010111 {
+                code + 256
+            } else {
+                code = (code << 1) | bit(1);
+                match code {
+                    0x30..=0xbf => code - 0x30,
+                    0xc0..=0xc7 => code - 0xc0 + 280,
+                    _ => ((code << 1) | bit(1)) - 0x190 + 144,
+                }
+            };
+
+            match symbol {
+                0..=255 => output.push(symbol as u8),
+                256 => return output,
+                _ => {
+                    let index = symbol as usize - 257;
+                    let length = LENGTH_BASE[index] as usize + bit(LENGTH_EXTRA[index]) as usize;
+                    let index = huffman(&mut bit, 5) as usize;
+                    let distance =
+                        DISTANCE_BASE[index] as usize + bit(DISTANCE_EXTRA[index]) as usize;
+
+                    for _ in 0..length {
+                        output.push(output[output.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xcbf43926);
+    }
+
+    #[test]
+    fn crc32_incremental() {
+        let mut a = Crc32::new();
+        a.update(b"1234");
+        a.update(b"56789");
+        let mut b = Crc32::new();
+        b.update(b"123456789");
+        assert_eq!(a.finish(), b.finish());
+    }
+
+    #[test]
+    fn adler32_check_value() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(adler32(&[]), 1);
+    }
+
+    #[test]
+    fn deflate_empty() {
+        // fixed block header followed by the end of block code
+        assert_eq!(deflate(&[]), vec![0x03, 0x00]);
+    }
+
+    #[test]
+    fn deflate_compresses_repetition() {
+        let data = vec![7; 10000];
+        let compressed = deflate(&data);
+        assert!(compressed.len() < 100);
+    }
+
+    #[test]
+    fn deflate_round_trip() {
+        let mut noise = vec![];
+        let mut state: u32 = 12345;
+        for _ in 0..70000 {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            noise.push((state >> 16) as u8);
+        }
+        let repeated: Vec<u8> = (0..100000).map(|index| (index % 251) as u8).collect();
+        let mut mixed = noise[..40000].to_vec();
+        mixed.extend_from_slice(&noise[..40000]);
+
+        for data in [
+            vec![],
+            b"a".to_vec(),
+            b"abcabcabcabcabc the quick brown fox, the quick brown fox".to_vec(),
+            vec![0; 1000],
+            noise,
+            repeated,
+            mixed,
+        ] {
+            assert_eq!(inflate(&deflate(&data)), data);
+        }
+    }
+
+    #[test]
+    fn deflate_known_bytes() {
+        // both decode with zlib's inflate
+        assert_eq!(deflate(b"a"), vec![0x4b, 0x04, 0x00]);
+        assert_eq!(deflate(b"aaaaaaaaaa"), vec![0x4b, 0x84, 0x03, 0x00]);
+
+        // matches with extra length and distance bits
+        let mut data = b"the quick brown fox jumps over the lazy dog, ".repeat(2);
+        data.pop();
+        data.pop();
+        data.extend_from_slice(&[b'x'; 300]);
+        assert_eq!(
+            deflate(&data),
+            vec![
+                0x2b, 0xc9, 0x48, 0x55, 0x28, 0x2c, 0xcd, 0x4c, 0xce, 0x56, 0x48, 0x2a, 0xca, 0x2f,
+                0xcf, 0x53, 0x48, 0xcb, 0xaf, 0x50, 0xc8, 0x2a, 0xcd, 0x2d, 0x28, 0x56, 0xc8, 0x2f,
+                0x4b, 0x2d, 0x52, 0x00, 0x49, 0xe7, 0x24, 0x56, 0x55, 0x2a, 0xa4, 0xe4, 0xa7, 0xeb,
+                0x80, 0x79, 0x44, 0x2a, 0xae, 0x18, 0x05, 0x44, 0x03, 0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn zlib_header() {
+        let compressed = zlib_compress(b"abc");
+        assert_eq!((compressed[0] as u16 * 256 + compressed[1] as u16) % 31, 0);
+        assert_eq!(compressed[0] & 0x0f, 8);
+        assert_eq!(
+            &compressed[compressed.len() - 4..],
+            &adler32(b"abc").to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn write_png_structure() {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.set_pixel(0, 0, &Color::new_color(1.0, 0.0, 0.0));
+
+        let mut buffer = vec![];
+        canvas
+            .write_png(&mut buffer, BitDepth::Eight)
+            .expect("unable to write png");
+
+        assert_eq!(&buffer[..8], &PNG_SIGNATURE);
+
+        let chunks = chunks(&buffer);
+        let kinds: Vec<&str> = chunks.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(kinds, vec!["IHDR", "IDAT", "IEND"]);
+        assert_eq!(chunks[0].1, vec![0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0]);
+        assert!(chunks[2].1.is_empty());
+
+        let idat = &chunks[1].1;
+        let scanlines = inflate(&idat[2..idat.len() - 4]);
+        let mut expect = vec![0; 20];
+        expect[1] = 255;
+        assert_eq!(scanlines, expect);
+        assert_eq!(&idat[idat.len() - 4..], &adler32(&expect).to_be_bytes());
+    }
+
+    #[test]
+    fn write_png_16_bit_header() {
+        let canvas = Canvas::new(1, 1);
+        let mut buffer = vec![];
+        canvas
+            .write_png(&mut buffer, BitDepth::Sixteen)
+            .expect("unable to write png");
+
+        let chunks = chunks(&buffer);
+        assert_eq!(chunks[0].1[8], 16);
+    }
+
+    #[test]
+    fn write_png_empty_canvas() {
+        let canvas = Canvas::new(0, 4);
+        let mut buffer = vec![];
+        assert!(canvas.write_png(&mut buffer, BitDepth::Eight).is_err());
+    }
+
+    #[test]
+    fn save_to_png_path() {
+        let file = TempFile::new("save_to_png_path.png");
+        let canvas = Canvas::new(4, 4);
+        canvas
+            .save_to_png(file.path(), BitDepth::Eight)
+            .expect("unable to save png");
+
+        let saved = std::fs::read(file.path()).expect("unable to read png");
+        assert_eq!(&saved[..8], &PNG_SIGNATURE);
+    }
+}
@@ -0,0 +1,487 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::color::color::Color;
+
+use super::{canvas::Canvas, error::ImageError, tone::ColorEncoding};
+
+const PPM_LINE_WIDTH: usize = 70;
+
+impl Canvas {
+    pub fn save_to_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_to_ppm_with(path, &ColorEncoding::default())
+    }
+
+    pub fn save_to_ppm_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm_with(&mut writer, encoding)?;
+        writer.flush()
+    }
+
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_ppm_with(writer, &ColorEncoding::default())
+    }
+
+    pub fn write_ppm_with<W: Write>(
+        &self,
+        writer: &mut W,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        write!(writer, "P3\n{} {}\n255\n", self.width, self.height)?;
+
+        let mut line = String::with_capacity(PPM_LINE_WIDTH);
+        for row in self.rows() {
+            for pixel in row.iter() {
+                for value in [pixel.red(), pixel.green(), pixel.blue()] {
+                    let value = encoding.to_byte(value).to_string();
+
+                    if !line.is_empty() && line.len() + 1 + value.len() > PPM_LINE_WIDTH {
+                        writeln!(writer, "{}", line)?;
+                        line.clear();
+                    }
+
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line += &value;
+                }
+            }
+
+            writeln!(writer, "{}", line)?;
+            line.clear();
+        }
+
+        Ok(())
+    }
+
+    pub fn save_to_ppm_binary<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_to_ppm_binary_with(path, &ColorEncoding::default())
+    }
+
+    pub fn save_to_ppm_binary_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm_binary_with(&mut writer, encoding)?;
+        writer.flush()
+    }
+
+    pub fn write_ppm_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_ppm_binary_with(writer, &ColorEncoding::default())
+    }
+
+    pub fn write_ppm_binary_with<W: Write>(
+        &self,
+        writer: &mut W,
+        encoding: &ColorEncoding,
+    ) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+
+        let mut data = Vec::with_capacity(self.pixels.len() * 3);
+        for pixel in self.pixels.iter() {
+            data.push(encoding.to_byte(pixel.red()));
+            data.push(encoding.to_byte(pixel.green()));
+            data.push(encoding.to_byte(pixel.blue()));
+        }
+
+        writer.write_all(&data)
+    }
+
+    pub fn load_ppm<P: AsRef<Path>>(path: P) -> Result<Canvas, ImageError> {
+        Canvas::parse_ppm(&fs::read(path)?)
+    }
+
+    pub fn read_ppm<R: Read>(reader: &mut R) -> Result<Canvas, ImageError> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        Canvas::parse_ppm(&data)
+    }
+
+    pub fn parse_ppm(data: &[u8]) -> Result<Canvas, ImageError> {
+        let mut parser = PpmParser { data, position: 0 };
+
+        let magic = parser.token()?;
+        let (channels, binary) = match magic {
+            b"P2" => (1, false),
+            b"P3" => (3, false),
+            b"P5" => (1, true),
+            b"P6" => (3, true),
+            _ => {
+                return Err(ImageError::UnsupportedFormat(
+                    String::from_utf8_lossy(magic).into_owned(),
+                ))
+            }
+        };
+
+        let width = parser.number()?;
+        let height = parser.number()?;
+        let max_value = parser.number()?;
+
+        if max_value == 0 || max_value > 65535 {
+            return Err(ImageError::InvalidHeader(format!(
+                "maximum value {} is out of range",
+                max_value
+            )));
+        }
+
+        let size = width.checked_mul(height).ok_or_else(|| {
+            ImageError::InvalidHeader(format!("{}x{} is too large", width, height))
+        })?;
+
+        // the header alone can't be trusted to size the canvas, a binary
+        // raster has to be all there and a plain one grows as it is read
+        let mut pixels = vec![];
+        if binary {
+            parser.single_whitespace()?;
+
+            let bytes_per_sample = if max_value > 255 { 2 } else { 1 };
+            let length = size
+                .checked_mul(channels * bytes_per_sample)
+                .ok_or_else(|| {
+                    ImageError::InvalidHeader(format!("{}x{} is too large", width, height))
+                })?;
+            if length > parser.remaining() {
+                return Err(ImageError::UnexpectedEof);
+            }
+
+            pixels.reserve_exact(size);
+        }
+
+        let mut samples = [0.0; 3];
+
+        for _ in 0..size {
+            for sample in samples.iter_mut().take(channels) {
+                let value = if binary {
+                    parser.binary_sample(max_value)?
+                } else {
+                    parser.number()?
+                };
+
+                if value > max_value {
+                    return Err(ImageError::InvalidValue(format!(
+                        "{} is larger than {}",
+                        value, max_value
+                    )));
+                }
+
+                *sample = value as f64 / max_value as f64;
+            }
+
+            pixels.push(if channels == 1 {
+                Color::new_color(samples[0], samples[0], samples[0])
+            } else {
+                Color::new_color(samples[0], samples[1], samples[2])
+            });
+        }
+
+        Canvas::from_pixels(width, height, pixels)
+            .map_err(|error| ImageError::InvalidHeader(error.to_string()))
+    }
+}
+
+struct PpmParser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PpmParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.data.get(self.position) {
+            if byte == b'#' {
+                while let Some(&byte) = self.data.get(self.position) {
+                    if byte == b'\n' || byte == b'\r' {
+                        break;
+                    }
+                    self.position += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<&'a [u8], ImageError> {
+        self.skip_whitespace();
+
+        let start = self.position;
+        while let Some(&byte) = self.data.get(self.position) {
+            if byte.is_ascii_whitespace() || byte == b'#' {
+                break;
+            }
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Err(ImageError::UnexpectedEof);
+        }
+
+        Ok(&self.data[start..self.position])
+    }
+
+    fn number(&mut self) -> Result<usize, ImageError> {
+        let token = self.token()?;
+        std::str::from_utf8(token)
+            .ok()
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| ImageError::InvalidValue(String::from_utf8_lossy(token).into_owned()))
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    fn single_whitespace(&mut self) -> Result<(), ImageError> {
+        match self.data.get(self.position) {
+            Some(byte) if byte.is_ascii_whitespace() => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(_) => Err(ImageError::InvalidHeader(String::from(
+                "expected whitespace before raster",
+            ))),
+            None => Err(ImageError::UnexpectedEof),
+        }
+    }
+
+    fn binary_sample(&mut self, max_value: usize) -> Result<usize, ImageError> {
+        let width = if max_value > 255 { 2 } else { 1 };
+        let bytes = self
+            .data
+            .get(self.position..self.position + width)
+            .ok_or(ImageError::UnexpectedEof)?;
+        self.position += width;
+
+        Ok(bytes
+            .iter()
+            .fold(0, |value, &byte| (value << 8) | byte as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::temp_file::TempFile;
+    use super::*;
+
+    fn write_to_string(canvas: &Canvas) -> String {
+        let mut buffer = vec![];
+        canvas.write_ppm(&mut buffer).expect("unable to write ppm");
+        String::from_utf8(buffer).expect("ppm is not utf8")
+    }
+
+    #[test]
+    fn write_ppm_header() {
+        let canvas = Canvas::new(5, 3);
+        let ppm = write_to_string(&canvas);
+        assert!(ppm.starts_with("P3\n5 3\n255\n"));
+    }
+
+    #[test]
+    fn write_ppm_pixel_data() {
+        let mut canvas = Canvas::new(5, 3);
+        canvas.set_pixel(0, 0, &Color::new_color(1.5, 0.0, 0.0));
+        canvas.set_pixel(2, 1, &Color::new_color(0.0, 0.5, 0.0));
+        canvas.set_pixel(4, 2, &Color::new_color(-0.5, 0.0, 1.0));
+
+        let ppm = write_to_string(&canvas);
+        let lines: Vec<&str> = ppm.lines().skip(3).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "255 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+                "0 0 0 0 0 0 0 128 0 0 0 0 0 0 0",
+                "0 0 0 0 0 0 0 0 0 0 0 0 0 0 255",
+            ]
+        );
+    }
+
+    #[test]
+    fn write_ppm_long_lines() {
+        let mut canvas = Canvas::new(10, 2);
+        let color = Color::new_color(1.0, 0.8, 0.6);
+        for y in 0..2 {
+            for x in 0..10 {
+                canvas.set_pixel(x, y, &color);
+            }
+        }
+
+        let ppm = write_to_string(&canvas);
+        let lines: Vec<&str> = ppm.lines().skip(3).collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                "153 255 204 153 255 204 153 255 204 153 255 204 153",
+                "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+                "153 255 204 153 255 204 153 255 204 153 255 204 153",
+            ]
+        );
+        assert!(lines.iter().all(|line| line.len() <= 70));
+    }
+
+    #[test]
+    fn write_ppm_ends_with_newline() {
+        let canvas = Canvas::new(5, 3);
+        let ppm = write_to_string(&canvas);
+        assert!(ppm.ends_with('\n'));
+    }
+
+    #[test]
+    fn save_to_ppm_path() {
+        let file = TempFile::new("save_to_ppm_path.ppm");
+        let canvas = Canvas::new(2, 2);
+        canvas.save_to_ppm(file.path()).expect("unable to save ppm");
+
+        let saved = fs::read_to_string(file.path()).expect("unable to read ppm");
+        assert_eq!(saved, write_to_string(&canvas));
+    }
+
+    #[test]
+    fn save_to_ppm_missing_directory() {
+        let path = std::env::temp_dir()
+            .join("save_to_ppm_missing_directory")
+            .join("image.ppm");
+        let canvas = Canvas::new(2, 2);
+        assert!(canvas.save_to_ppm(path).is_err());
+    }
+
+    #[test]
+    fn write_ppm_binary() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.set_pixel(0, 0, &Color::new_color(1.0, 0.5, 0.0));
+        canvas.set_pixel(1, 0, &Color::new_color(0.0, 0.0, 2.0));
+
+        let mut buffer = vec![];
+        canvas
+            .write_ppm_binary(&mut buffer)
+            .expect("unable to write ppm");
+
+        let mut expect = b"P6\n2 1\n255\n".to_vec();
+        expect.extend_from_slice(&[255, 128, 0, 0, 0, 255]);
+        assert_eq!(buffer, expect);
+    }
+
+    #[test]
+    fn round_trip_plain() {
+        let mut canvas = Canvas::new(10, 3);
+        canvas.set_pixel(0, 0, &Color::new_color(1.0, 0.0, 0.0));
+        canvas.set_pixel(9, 2, &Color::new_color(0.2, 0.4, 0.6));
+
+        let mut buffer = vec![];
+        canvas.write_ppm(&mut buffer).expect("unable to write ppm");
+        let result = Canvas::read_ppm(&mut buffer.as_slice()).expect("unable to read ppm");
+
+        assert_eq!(result.width, 10);
+        assert_eq!(result.height, 3);
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(a.equals(b));
+        }
+    }
+
+    #[test]
+    fn round_trip_binary() {
+        let mut canvas = Canvas::new(4, 4);
+        canvas.set_pixel(1, 2, &Color::new_color(1.0, 0.0, 1.0));
+
+        let file = TempFile::new("round_trip_binary.ppm");
+        canvas
+            .save_to_ppm_binary(file.path())
+            .expect("unable to save ppm");
+        let result = Canvas::load_ppm(file.path()).expect("unable to load ppm");
+
+        assert_eq!(result.width, 4);
+        assert_eq!(result.height, 4);
+        for (a, b) in canvas.pixels.iter().zip(result.pixels.iter()) {
+            assert!(a.equals(b));
+        }
+    }
+
+    #[test]
+    fn parse_comments_and_whitespace() {
+        let data = b"P3\n# a comment\n2 1 # trailing comment\n\n  255\n255 0 0\t0 255 0\n";
+        let canvas = Canvas::parse_ppm(data).expect("unable to parse ppm");
+
+        assert!(canvas
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(1.0, 0.0, 0.0)));
+        assert!(canvas
+            .get_pixel(1, 0)
+            .equals(&Color::new_color(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn parse_scales_by_max_value() {
+        let data = b"P3\n1 1\n100\n50 100 0\n";
+        let canvas = Canvas::parse_ppm(data).expect("unable to parse ppm");
+        assert!(canvas
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(0.5, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn parse_plain_grayscale() {
+        let data = b"P2\n2 1\n4\n1 4\n";
+        let canvas = Canvas::parse_ppm(data).expect("unable to parse pgm");
+        assert!(canvas
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(0.25, 0.25, 0.25)));
+        assert!(canvas
+            .get_pixel(1, 0)
+            .equals(&Color::new_color(1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn parse_binary_grayscale_16_bit() {
+        let mut data = b"P5 2 1 65535\n".to_vec();
+        data.extend_from_slice(&[0xff, 0xff, 0x00, 0x00]);
+        let canvas = Canvas::parse_ppm(&data).expect("unable to parse pgm");
+        assert!(canvas
+            .get_pixel(0, 0)
+            .equals(&Color::new_color(1.0, 1.0, 1.0)));
+        assert!(canvas
+            .get_pixel(1, 0)
+            .equals(&Color::new_color(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn parse_unsupported_format() {
+        let result = Canvas::parse_ppm(b"P4\n1 1\n");
+        assert!(matches!(result, Err(ImageError::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn parse_truncated_raster() {
+        let result = Canvas::parse_ppm(b"P6\n2 2\n255\n\x00\x00\x00");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn parse_header_larger_than_data() {
+        let result = Canvas::parse_ppm(b"P6\n200000 200000\n255\n\0\0\0");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+
+        let result = Canvas::parse_ppm(b"P3\n200000 200000\n255\n0 0 0\n");
+        assert!(matches!(result, Err(ImageError::UnexpectedEof)));
+
+        let result = Canvas::parse_ppm(b"P6\n18446744073709551615 2\n255\n\0");
+        assert!(matches!(result, Err(ImageError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn parse_value_too_large() {
+        let result = Canvas::parse_ppm(b"P3\n1 1\n255\n256 0 0\n");
+        assert!(matches!(result, Err(ImageError::InvalidValue(_))));
+    }
+}
@@ -0,0 +1,30 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+// a file in the temp directory for tests that go through a path. the name
+// includes the process id so concurrent test runs don't share files, and
+// the file is removed again when this is dropped
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    pub fn new(name: &str) -> TempFile {
+        TempFile {
+            path: env::temp_dir().join(format!("{}-{}", process::id(), name)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,167 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    Clamp,
+    Exposure(f64),
+    Reinhard,
+    Aces,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferFunction {
+    Linear,
+    Srgb,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorEncoding {
+    pub tone_map: ToneMap,
+    pub transfer: TransferFunction,
+}
+
+impl ToneMap {
+    pub fn apply(&self, value: f64) -> f64 {
+        let value = value.max(0.0);
+        let mapped = match self {
+            ToneMap::Clamp => value,
+            ToneMap::Exposure(exposure) => 1.0 - (-value * exposure).exp(),
+            ToneMap::Reinhard => value / (1.0 + value),
+            // Narkowicz's curve fit of the ACES filmic reference transform
+            ToneMap::Aces => {
+                (value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14)
+            }
+        };
+        mapped.clamp(0.0, 1.0)
+    }
+}
+
+impl TransferFunction {
+    pub fn encode(&self, value: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => value,
+            TransferFunction::Srgb => {
+                if value <= 0.0031308 {
+                    value * 12.92
+                } else {
+                    1.055 * value.powf(1.0 / 2.4) - 0.055
+                }
+            }
+        }
+    }
+
+    pub fn decode(&self, value: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => value,
+            TransferFunction::Srgb => {
+                if value <= 0.04045 {
+                    value / 12.92
+                } else {
+                    ((value + 0.055) / 1.055).powf(2.4)
+                }
+            }
+        }
+    }
+}
+
+impl ColorEncoding {
+    pub fn new(tone_map: ToneMap, transfer: TransferFunction) -> ColorEncoding {
+        ColorEncoding { tone_map, transfer }
+    }
+
+    pub fn srgb(tone_map: ToneMap) -> ColorEncoding {
+        ColorEncoding::new(tone_map, TransferFunction::Srgb)
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        self.transfer.encode(self.tone_map.apply(value))
+    }
+
+    pub fn to_byte(&self, value: f64) -> u8 {
+        (self.apply(value) * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+
+    pub fn to_word(&self, value: f64) -> u16 {
+        (self.apply(value) * 65535.0).round().clamp(0.0, 65535.0) as u16
+    }
+}
+
+impl Default for ColorEncoding {
+    fn default() -> Self {
+        ColorEncoding::new(ToneMap::Clamp, TransferFunction::Linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{canvas::canvas::Canvas, color::color::Color, math::compare::equal};
+
+    use super::*;
+
+    #[test]
+    fn clamp() {
+        assert!(equal(ToneMap::Clamp.apply(0.25), 0.25));
+        assert!(equal(ToneMap::Clamp.apply(1.5), 1.0));
+        assert!(equal(ToneMap::Clamp.apply(-0.5), 0.0));
+    }
+
+    #[test]
+    fn exposure() {
+        assert!(equal(ToneMap::Exposure(1.0).apply(1.0), 0.63212));
+        assert!(equal(ToneMap::Exposure(2.0).apply(0.5), 0.63212));
+        assert!(ToneMap::Exposure(1.0).apply(100.0) <= 1.0);
+    }
+
+    #[test]
+    fn reinhard() {
+        assert!(equal(ToneMap::Reinhard.apply(1.0), 0.5));
+        assert!(equal(ToneMap::Reinhard.apply(3.0), 0.75));
+        assert!(ToneMap::Reinhard.apply(1000.0) < 1.0);
+    }
+
+    #[test]
+    fn aces() {
+        assert!(equal(ToneMap::Aces.apply(0.0), 0.0));
+        assert!(equal(ToneMap::Aces.apply(1.0), 0.80377));
+        assert!(equal(ToneMap::Aces.apply(1000.0), 1.0));
+    }
+
+    #[test]
+    fn srgb_transfer() {
+        let srgb = TransferFunction::Srgb;
+        assert!(equal(srgb.encode(0.0), 0.0));
+        assert!(equal(srgb.encode(1.0), 1.0));
+        assert!(equal(srgb.encode(0.5), 0.73536));
+        assert!(equal(srgb.encode(0.002), 0.02584));
+        assert!(equal(srgb.decode(srgb.encode(0.2)), 0.2));
+    }
+
+    #[test]
+    fn default_encoding_matches_ppm_quantization() {
+        let encoding = ColorEncoding::default();
+        assert_eq!(encoding.to_byte(1.5), 255);
+        assert_eq!(encoding.to_byte(0.5), 128);
+        assert_eq!(encoding.to_byte(-0.5), 0);
+        assert_eq!(encoding.to_word(1.0), 65535);
+    }
+
+    #[test]
+    fn srgb_encoding_to_byte() {
+        let encoding = ColorEncoding::srgb(ToneMap::Clamp);
+        assert_eq!(encoding.to_byte(0.5), 188);
+        assert_eq!(encoding.to_byte(0.22), 129);
+    }
+
+    #[test]
+    fn export_with_encoding() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.set_pixel(0, 0, &Color::new_color(0.5, 1.0, 3.0));
+
+        let mut buffer = vec![];
+        canvas
+            .write_ppm_binary_with(&mut buffer, &ColorEncoding::srgb(ToneMap::Reinhard))
+            .expect("unable to write ppm");
+
+        let expect = [0.5 / 1.5, 0.5, 0.75]
+            .map(|value| (TransferFunction::Srgb.encode(value) * 255.0).round() as u8);
+        assert_eq!(&buffer[buffer.len() - 3..], &expect);
+    }
+}
@@ -1,27 +1,136 @@
-use crate::math::tuple::Tuple;
+use crate::math::compare::{ApproxEq, Tolerance};
 
-pub type Color = Tuple;
+#[derive(Debug, Clone, Copy)]
+// written as a plain [red, green, blue] array
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[f64; 3]", into = "[f64; 3]")
+)]
+pub struct Color {
+    pub red: f64,
+    pub green: f64,
+    pub blue: f64,
+}
+
+impl std::ops::Add for Color {
+    type Output = Color;
+    fn add(self, rhs: Color) -> Self::Output {
+        Color::new_color(
+            self.red + rhs.red,
+            self.green + rhs.green,
+            self.blue + rhs.blue,
+        )
+    }
+}
+
+impl std::ops::AddAssign for Color {
+    fn add_assign(&mut self, rhs: Color) {
+        self.red += rhs.red;
+        self.green += rhs.green;
+        self.blue += rhs.blue;
+    }
+}
+
+impl std::ops::Sub for Color {
+    type Output = Color;
+    fn sub(self, rhs: Color) -> Self::Output {
+        Color::new_color(
+            self.red - rhs.red,
+            self.green - rhs.green,
+            self.blue - rhs.blue,
+        )
+    }
+}
+
+// the hadamard product, used to filter one color through another
+impl std::ops::Mul for Color {
+    type Output = Color;
+    fn mul(self, rhs: Color) -> Self::Output {
+        Color::new_color(
+            self.red * rhs.red,
+            self.green * rhs.green,
+            self.blue * rhs.blue,
+        )
+    }
+}
+
+impl std::ops::MulAssign for Color {
+    fn mul_assign(&mut self, rhs: Color) {
+        self.red *= rhs.red;
+        self.green *= rhs.green;
+        self.blue *= rhs.blue;
+    }
+}
+
+impl std::ops::Mul<f64> for Color {
+    type Output = Color;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Color::new_color(self.red * rhs, self.green * rhs, self.blue * rhs)
+    }
+}
+
+impl std::ops::MulAssign<f64> for Color {
+    fn mul_assign(&mut self, rhs: f64) {
+        self.red *= rhs;
+        self.green *= rhs;
+        self.blue *= rhs;
+    }
+}
+
+impl std::ops::DivAssign<f64> for Color {
+    fn div_assign(&mut self, rhs: f64) {
+        self.red /= rhs;
+        self.green /= rhs;
+        self.blue /= rhs;
+    }
+}
+
+impl ApproxEq for Color {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.equal(self.red, other.red)
+            && tolerance.equal(self.green, other.green)
+            && tolerance.equal(self.blue, other.blue)
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Color) -> bool {
+        self.equals(other)
+    }
+}
+
+impl From<[f64; 3]> for Color {
+    fn from([red, green, blue]: [f64; 3]) -> Self {
+        Color::new_color(red, green, blue)
+    }
+}
+
+impl From<Color> for [f64; 3] {
+    fn from(color: Color) -> Self {
+        [color.red, color.green, color.blue]
+    }
+}
 
 impl Color {
     pub fn new_color(red: f64, green: f64, blue: f64) -> Color {
-        Color {
-            x: red,
-            y: green,
-            z: blue,
-            w: 0.0,
-        }
+        Color { red, green, blue }
     }
 
     pub fn red(&self) -> f64 {
-        self.x
+        self.red
     }
 
     pub fn green(&self) -> f64 {
-        self.y
+        self.green
     }
 
     pub fn blue(&self) -> f64 {
-        self.z
+        self.blue
+    }
+
+    pub fn equals(&self, other: &Color) -> bool {
+        self.approx_eq(other, Tolerance::default())
     }
 }
 
@@ -43,7 +152,7 @@ mod tests {
         let b = Color::new_color(0.7, 0.1, 0.25);
         let result = a + b;
         let expect = Color::new_color(1.6, 0.7, 1.0);
-        assert_eq!(result.equals(&expect), true);
+        assert!(result.equals(&expect));
     }
 
     #[test]
@@ -52,7 +161,7 @@ mod tests {
         let b = Color::new_color(0.7, 0.1, 0.25);
         let result = a - b;
         let expect = Color::new_color(0.2, 0.5, 0.5);
-        assert_eq!(result.equals(&expect), true);
+        assert!(result.equals(&expect));
     }
 
     #[test]
@@ -63,7 +172,7 @@ mod tests {
         let result = a * b;
         let expect = Color::new_color(0.9, 0.2, 0.04);
 
-        assert_eq!(result.equals(&expect), true);
+        assert!(result.equals(&expect));
     }
 
     #[test]
@@ -72,6 +181,23 @@ mod tests {
         result *= 2.0;
         let expect = Color::new_color(0.4, 0.6, 0.8);
 
-        assert_eq!(result.equals(&expect), true);
+        assert!(result.equals(&expect));
+    }
+
+    #[test]
+    fn scale_by_value() {
+        let a = Color::new_color(0.2, 0.3, 0.4);
+        let expect = Color::new_color(0.4, 0.6, 0.8);
+        assert!((a * 2.0).equals(&expect));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let color = Color::new_color(0.25, 0.5, 1.0);
+
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, "[0.25,0.5,1.0]");
+        assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
     }
 }
@@ -1 +1,5 @@
-mod math;
\ No newline at end of file
+#![allow(clippy::module_inception)]
+
+pub mod canvas;
+pub mod color;
+pub mod math;
@@ -1,8 +1,4 @@
-mod canvas;
-mod color;
-mod math;
-
-use crate::math::tuple::Tuple;
+use the_ray_tracer_challenege::math::tuple::Tuple;
 
 fn main() {
     println!("Hello, world!");
@@ -1,5 +1,161 @@
-const epsilon: f64 = 0.0001;
+use super::scalar::Scalar;
+
+pub const EPSILON: f64 = 0.0001;
+
+// a determinant smaller than this fraction of the largest one possible for
+// the matrix's row lengths is treated as singular
+pub const SINGULAR_EPSILON: f64 = 1e-12;
 
 pub fn equal(a: f64, b: f64) -> bool {
-    (a - b).abs() < epsilon
+    (a - b).abs() < EPSILON
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tolerance {
+    Absolute(f64),
+    // relative is scaled by the larger of the two magnitudes, absolute still
+    // applies so values around zero can compare equal
+    Relative { absolute: f64, relative: f64 },
+    // the number of representable values of the compared type between the
+    // two values, so f32 counts f32 steps
+    Ulps { absolute: f64, ulps: u64 },
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance::Absolute(EPSILON)
+    }
+}
+
+impl Tolerance {
+    pub fn equal<T: Scalar>(&self, a: T, b: T) -> bool {
+        if a == b {
+            return true;
+        }
+
+        let (bits_a, bits_b) = (a.ordered_bits(), b.ordered_bits());
+        let (a, b) = (a.to_f64(), b.to_f64());
+        let difference = (a - b).abs();
+        match *self {
+            Tolerance::Absolute(absolute) => difference < absolute,
+            Tolerance::Relative { absolute, relative } => {
+                difference < absolute || difference <= relative * a.abs().max(b.abs())
+            }
+            Tolerance::Ulps { absolute, ulps } => {
+                if difference < absolute {
+                    return true;
+                }
+                if a.is_nan() || b.is_nan() {
+                    return false;
+                }
+                bits_a.abs_diff(bits_b) <= ulps
+            }
+        }
+    }
+}
+
+// the math and color types compare field by field with a Tolerance. their
+// equals and PartialEq both use Tolerance::default(), so == on them is
+// approximate and not transitive
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool;
+}
+
+impl ApproxEq for f64 {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.equal(*self, *other)
+    }
+}
+
+// compares the determinant against the hadamard bound, the product of the
+// row lengths, so the check doesn't depend on the scale of the matrix
+pub fn near_singular(determinant: f64, rows: impl Iterator<Item = f64>, epsilon: f64) -> bool {
+    let bound: f64 = rows.product();
+    bound == 0.0 || !determinant.is_finite() || (determinant / bound).abs() < epsilon
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute() {
+        let tolerance = Tolerance::Absolute(0.01);
+        assert!(tolerance.equal(1.0, 1.005));
+        assert!(!tolerance.equal(1.0, 1.02));
+        assert!(!tolerance.equal(1e9, 1e9 + 1.0));
+    }
+
+    #[test]
+    fn relative() {
+        let tolerance = Tolerance::Relative {
+            absolute: 1e-9,
+            relative: 1e-6,
+        };
+        assert!(tolerance.equal(1e9, 1e9 + 100.0));
+        assert!(!tolerance.equal(1e9, 1e9 + 10000.0));
+        assert!(tolerance.equal(0.0, 1e-10));
+        assert!(!tolerance.equal(1e-3, 2e-3));
+    }
+
+    #[test]
+    fn ulps() {
+        let tolerance = Tolerance::Ulps {
+            absolute: 0.0,
+            ulps: 4,
+        };
+        let next = f64::from_bits(1.0_f64.to_bits() + 3);
+        assert!(tolerance.equal(1.0, next));
+        assert!(!tolerance.equal(1.0, 1.0 + 1e-12));
+        assert!(tolerance.equal(0.0, -0.0));
+        assert!(!tolerance.equal(f64::NAN, f64::NAN));
+
+        let smallest = f64::from_bits(1);
+        assert!(tolerance.equal(smallest, -smallest));
+    }
+
+    #[test]
+    fn ulps_single_precision() {
+        let tolerance = Tolerance::Ulps {
+            absolute: 0.0,
+            ulps: 4,
+        };
+        let next = f32::from_bits(1.0_f32.to_bits() + 3);
+        assert!(tolerance.equal(1.0_f32, next));
+        assert!(!tolerance.equal(1.0_f32, f32::from_bits(1.0_f32.to_bits() + 5)));
+        assert!(tolerance.equal(f32::from_bits(1), -f32::from_bits(1)));
+        assert!(!tolerance.equal(f32::NAN, f32::NAN));
+    }
+
+    #[test]
+    fn default_matches_equal() {
+        assert!(Tolerance::default().equal(1.0, 1.00009));
+        assert!(!Tolerance::default().equal(1.0, 1.0002));
+        assert_eq!(
+            Tolerance::default().equal(2.0, 2.00005),
+            equal(2.0, 2.00005)
+        );
+    }
+
+    #[test]
+    fn approx_eq_for_values() {
+        assert!(1.0.approx_eq(&1.00001, Tolerance::default()));
+        assert!(!1.0.approx_eq(&1.1, Tolerance::default()));
+    }
+
+    #[test]
+    fn singular_is_scale_independent() {
+        assert!(!near_singular(
+            1e-15,
+            [1e-5, 1e-5, 1e-5].into_iter(),
+            SINGULAR_EPSILON
+        ));
+        assert!(near_singular(
+            1e-14,
+            [2.0, 3.0].into_iter(),
+            SINGULAR_EPSILON
+        ));
+        assert!(near_singular(0.0, [1.0, 1.0].into_iter(), SINGULAR_EPSILON));
+        assert!(near_singular(1.0, [0.0, 1.0].into_iter(), SINGULAR_EPSILON));
+    }
 }
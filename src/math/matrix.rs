@@ -1,46 +1,106 @@
-use std::ops::Neg;
-
-use super::{compare::equal, tuple::Tuple};
+use super::{
+    compare::{self, ApproxEq, Tolerance},
+    ops::forward_val_binop,
+    scalar::Scalar,
+    tuple::Tuple,
+};
 
 #[derive(Debug, Clone)]
-pub struct Matrix {
+pub struct Matrix<T = f64> {
     pub width: usize,
     pub height: usize,
-    pub data: Vec<f64>,
+    pub data: Vec<T>,
 }
 
-impl std::ops::Mul<f64> for Matrix {
-    type Output = Matrix;
+impl<T: Scalar> std::ops::Mul<T> for Matrix<T> {
+    type Output = Matrix<T>;
 
-    fn mul(self, rhs: f64) -> Self::Output {
-        let mut data = vec![];
+    fn mul(mut self, rhs: T) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
 
-        for value in self.data {
-            data.push(value * rhs);
+impl<T: Scalar> std::ops::Mul<T> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.clone() * rhs
+    }
+}
+
+impl<T: Scalar> std::ops::MulAssign<T> for Matrix<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        for value in self.data.iter_mut() {
+            *value *= rhs;
         }
+    }
+}
 
-        Matrix {
-            width: self.width,
-            height: self.height,
-            data,
+impl<T: Scalar> std::ops::Add<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn add(self, rhs: &Matrix<T>) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<T: Scalar> std::ops::Sub<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn sub(self, rhs: &Matrix<T>) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<T: Scalar> std::ops::AddAssign<&Matrix<T>> for Matrix<T> {
+    fn add_assign(&mut self, rhs: &Matrix<T>) {
+        assert!(self.width == rhs.width && self.height == rhs.height);
+        for (value, other) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *value += *other;
+        }
+    }
+}
+
+impl<T: Scalar> std::ops::SubAssign<&Matrix<T>> for Matrix<T> {
+    fn sub_assign(&mut self, rhs: &Matrix<T>) {
+        assert!(self.width == rhs.width && self.height == rhs.height);
+        for (value, other) in self.data.iter_mut().zip(rhs.data.iter()) {
+            *value -= *other;
         }
     }
 }
 
-impl std::ops::Mul<&Matrix> for &Matrix {
-    type Output = Matrix;
-    fn mul(self, rhs: &Matrix) -> Self::Output {
+impl<T: Scalar> std::ops::Neg for Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
+    }
+}
+
+impl<T: Scalar> std::ops::Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    fn neg(self) -> Self::Output {
+        self * -T::ONE
+    }
+}
+
+impl<T: Scalar> std::ops::Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
         assert!(self.width == rhs.height);
         let mut data = vec![];
 
-        for index in 0..rhs.width * rhs.height {
+        for index in 0..self.height * rhs.width {
             let result_y = index / rhs.width;
             let result_x = index % rhs.width;
 
-            let mut result_value = 0.0;
-            for rhs_y in 0..rhs.height {
-                let left = self.get(result_y, rhs_y);
-                let right = rhs.get(rhs_y, result_x);
+            let mut result_value = T::ZERO;
+            for inner in 0..self.width {
+                let left = self.get(result_y, inner);
+                let right = rhs.get(inner, result_x);
                 result_value += left * right;
             }
 
@@ -49,23 +109,82 @@ impl std::ops::Mul<&Matrix> for &Matrix {
 
         Matrix {
             width: rhs.width,
-            height: rhs.height,
+            height: self.height,
             data,
         }
     }
 }
 
-impl std::ops::Mul<Tuple> for &Matrix {
-    type Output = Tuple;
+impl<T: Scalar> std::ops::Mul<Tuple<T>> for &Matrix<T> {
+    type Output = Tuple<T>;
+
+    fn mul(self, rhs: Tuple<T>) -> Self::Output {
+        assert!(self.width == 4 && self.height == 4);
+        let rhs = rhs.to_array();
+        let row = |y: usize| {
+            let data: &[T; 4] = self.data[y * 4..y * 4 + 4].try_into().unwrap();
+            T::dot4(data, &rhs)
+        };
 
-    fn mul(self, rhs: Tuple) -> Self::Output {
-        let rhs_matrix = Matrix::new(1, 4, vec![rhs.x, rhs.y, rhs.z, rhs.w]);
-        (self * &rhs_matrix).to_tuple()
+        Tuple::new_tuple(row(0), row(1), row(2), row(3))
     }
 }
 
-impl Matrix {
-    pub fn new(width: usize, height: usize, data: Vec<f64>) -> Matrix {
+impl<T: Scalar> std::ops::Mul<Tuple<T>> for Matrix<T> {
+    type Output = Tuple<T>;
+
+    fn mul(self, rhs: Tuple<T>) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<T: Scalar> std::ops::MulAssign<&Matrix<T>> for Matrix<T> {
+    fn mul_assign(&mut self, rhs: &Matrix<T>) {
+        *self = &*self * rhs;
+    }
+}
+
+forward_val_binop!(impl Add, add for Matrix<T>, Matrix<T>);
+forward_val_binop!(impl Sub, sub for Matrix<T>, Matrix<T>);
+forward_val_binop!(impl Mul, mul for Matrix<T>, Matrix<T>);
+
+// indexed as (row, column), the same order as get
+impl<T: Scalar> std::ops::Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (y, x): (usize, usize)) -> &Self::Output {
+        assert!(x < self.width && y < self.height);
+        &self.data[self.width * y + x]
+    }
+}
+
+impl<T: Scalar> std::ops::IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (y, x): (usize, usize)) -> &mut Self::Output {
+        assert!(x < self.width && y < self.height);
+        &mut self.data[self.width * y + x]
+    }
+}
+
+impl<T: Scalar> ApproxEq for Matrix<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self
+                .data
+                .iter()
+                .zip(other.data.iter())
+                .all(|(a, b)| tolerance.equal(*a, *b))
+    }
+}
+
+impl<T: Scalar> PartialEq for Matrix<T> {
+    fn eq(&self, other: &Matrix<T>) -> bool {
+        self.equals(other)
+    }
+}
+
+impl<T: Scalar> Matrix<T> {
+    pub fn new(width: usize, height: usize, data: Vec<T>) -> Matrix<T> {
         assert!(data.len() == width * height);
         Matrix {
             width,
@@ -74,15 +193,15 @@ impl Matrix {
         }
     }
 
-    pub fn new_identity(size: usize) -> Matrix {
+    pub fn new_identity(size: usize) -> Matrix<T> {
         let mut data = vec![];
         for i in 0..size * size {
             let row = i / size;
             let col = i % size;
             if row == col {
-                data.push(1.0);
+                data.push(T::ONE);
             } else {
-                data.push(0.0);
+                data.push(T::ZERO);
             }
         }
         Matrix {
@@ -92,31 +211,21 @@ impl Matrix {
         }
     }
 
-    pub fn get(&self, y: usize, x: usize) -> f64 {
+    pub fn get(&self, y: usize, x: usize) -> T {
         assert!(x < self.width && y < self.height);
-        return self.data[self.width * y + x];
+        self.data[self.width * y + x]
     }
 
-    pub fn equals(&self, other: &Matrix) -> bool {
-        if self.width != other.width || self.height != other.height {
-            return false;
-        }
-
-        for (index, value) in self.data.iter().enumerate() {
-            if equal(other.data[index], *value) == false {
-                return false;
-            }
-        }
-
-        return true;
+    pub fn equals(&self, other: &Matrix<T>) -> bool {
+        self.approx_eq(other, Tolerance::default())
     }
 
-    pub fn to_tuple(&self) -> Tuple {
+    pub fn to_tuple(&self) -> Tuple<T> {
         assert!((self.width == 4 && self.height == 1) || (self.width == 1 && self.height == 4));
         Tuple::new_tuple(self.data[0], self.data[1], self.data[2], self.data[3])
     }
 
-    pub fn transpose(&self) -> Matrix {
+    pub fn transpose(&self) -> Matrix<T> {
         let mut data = vec![];
 
         for x in 0..self.width {
@@ -132,7 +241,7 @@ impl Matrix {
         }
     }
 
-    pub fn submatrix(&self, row: usize, col: usize) -> Matrix {
+    pub fn submatrix(&self, row: usize, col: usize) -> Matrix<T> {
         let mut data = vec![];
 
         for y in 0..self.height {
@@ -152,11 +261,11 @@ impl Matrix {
         }
     }
 
-    pub fn minor(&self, row: usize, col: usize) -> f64 {
+    pub fn minor(&self, row: usize, col: usize) -> T {
         self.submatrix(row, col).determinant()
     }
 
-    pub fn cofactor(&self, row: usize, col: usize) -> f64 {
+    pub fn cofactor(&self, row: usize, col: usize) -> T {
         let minor = self.minor(row, col);
         if (row + col) % 2 == 0 {
             minor
@@ -165,52 +274,189 @@ impl Matrix {
         }
     }
 
-    pub fn determinate_2x2(&self) -> f64 {
+    pub fn determinate_2x2(&self) -> T {
         assert!(self.width == 2 && self.height == 2);
         (self.get(0, 0) * self.get(1, 1)) - (self.get(0, 1) * self.get(1, 0))
     }
 
-    pub fn determinant(&self) -> f64 {
-        if self.width == 2 && self.height == 2 {
-            return self.determinate_2x2();
-        }
+    // gaussian elimination with partial pivoting, the sign flips with
+    // every row swap
+    pub fn determinant(&self) -> T {
+        assert!(self.width == self.height);
+        let size = self.width;
+        let mut data = self.data.clone();
+        let mut determinant = T::ONE;
+
+        for column in 0..size {
+            let pivot = pivot_row(&data, size, column);
+            if data[pivot * size + column] == T::ZERO {
+                return T::ZERO;
+            }
+
+            if pivot != column {
+                swap_rows(&mut data, size, pivot, column);
+                determinant = -determinant;
+            }
 
-        let mut determinant = 0.0;
+            let pivot_value = data[column * size + column];
+            determinant *= pivot_value;
 
-        for index in 0..self.width {
-            let cofactor = self.cofactor(0, index);
-            let data = self.data[index];
-            determinant += data * cofactor;
+            for row in column + 1..size {
+                let factor = data[row * size + column] / pivot_value;
+                for x in column..size {
+                    let value = factor * data[column * size + x];
+                    data[row * size + x] -= value;
+                }
+            }
         }
 
-        return determinant;
+        determinant
     }
 
     pub fn is_invertable(&self) -> bool {
-        self.determinant() != 0.0
+        !self.is_near_singular(T::SINGULAR_EPSILON)
     }
 
-    pub fn inverse(&self) -> Option<Matrix> {
-        if self.is_invertable() == false {
+    pub fn is_near_singular(&self, epsilon: f64) -> bool {
+        let rows = self.data.chunks(self.width.max(1)).map(|row| {
+            row.iter()
+                .map(|value| value.to_f64().powi(2))
+                .sum::<f64>()
+                .sqrt()
+        });
+        compare::near_singular(self.determinant().to_f64(), rows, epsilon)
+    }
+
+    pub fn inverse(&self) -> Option<Matrix<T>> {
+        if !self.is_invertable() {
             return None;
         }
+        self.solve(&Matrix::new_identity(self.width))
+    }
 
-        let mut data = vec![];
-        for y in 0..self.height {
-            for x in 0..self.width {
-                data.push(self.cofactor(y, x));
+    // solves self * x = rhs for x with gauss-jordan elimination, every column
+    // of rhs is a separate system
+    pub fn solve(&self, rhs: &Matrix<T>) -> Option<Matrix<T>> {
+        assert!(self.width == self.height && self.height == rhs.height);
+        let size = self.width;
+        let columns = rhs.width;
+        let mut left = self.data.clone();
+        let mut right = rhs.data.clone();
+
+        for column in 0..size {
+            let pivot = pivot_row(&left, size, column);
+            if left[pivot * size + column] == T::ZERO {
+                return None;
+            }
+
+            swap_rows(&mut left, size, pivot, column);
+            swap_rows(&mut right, columns, pivot, column);
+
+            let pivot_value = left[column * size + column];
+            for value in left[column * size..(column + 1) * size].iter_mut() {
+                *value /= pivot_value;
+            }
+            for value in right[column * columns..(column + 1) * columns].iter_mut() {
+                *value /= pivot_value;
+            }
+
+            for row in 0..size {
+                let factor = left[row * size + column];
+                if row == column || factor == T::ZERO {
+                    continue;
+                }
+
+                for x in 0..size {
+                    let value = factor * left[column * size + x];
+                    left[row * size + x] -= value;
+                }
+                for x in 0..columns {
+                    let value = factor * right[column * columns + x];
+                    right[row * columns + x] -= value;
+                }
             }
         }
 
-        let result =
-            Matrix::new(self.width, self.height, data).transpose() * (1.0 / self.determinant());
+        Some(Matrix::new(columns, size, right))
+    }
+
+    pub fn cast<U: Scalar>(&self) -> Matrix<U> {
+        let data = self
+            .data
+            .iter()
+            .map(|value| U::from_f64(value.to_f64()))
+            .collect();
+        Matrix::new(self.width, self.height, data)
+    }
+
+    fn zip_with(&self, other: &Matrix<T>, operation: impl Fn(T, T) -> T) -> Matrix<T> {
+        assert!(self.width == other.width && self.height == other.height);
+        let data = self
+            .data
+            .iter()
+            .zip(other.data.iter())
+            .map(|(a, b)| operation(*a, *b))
+            .collect();
+
+        Matrix::new(self.width, self.height, data)
+    }
+}
+
+// written as an array of rows, the shape is taken from the rows
+#[cfg(feature = "serde")]
+impl<T: Scalar + serde::Serialize> serde::Serialize for Matrix<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let mut rows = serializer.serialize_seq(Some(self.height))?;
+        for y in 0..self.height {
+            rows.serialize_element(&self.data[y * self.width..(y + 1) * self.width])?;
+        }
+        rows.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Scalar + serde::Deserialize<'de>> serde::Deserialize<'de> for Matrix<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rows = Vec::<Vec<T>>::deserialize(deserializer)?;
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(y) = rows.iter().position(|row| row.len() != width) {
+            return Err(serde::de::Error::custom(format!(
+                "row {} has {} values, expected {}",
+                y,
+                rows[y].len(),
+                width
+            )));
+        }
 
-        Some(result)
+        Ok(Matrix::new(width, rows.len(), rows.concat()))
     }
 }
 
+fn pivot_row<T: Scalar>(data: &[T], size: usize, column: usize) -> usize {
+    (column..size)
+        .max_by(|a, b| {
+            data[a * size + column]
+                .abs()
+                .total_cmp(&data[b * size + column].abs())
+        })
+        .unwrap_or(column)
+}
+
+fn swap_rows<T>(data: &mut [T], width: usize, a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+
+    let (a, b) = (a.min(b), a.max(b));
+    let (top, bottom) = data.split_at_mut(b * width);
+    top[a * width..(a + 1) * width].swap_with_slice(&mut bottom[..width]);
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::compare::equal;
     use super::*;
 
     #[test]
@@ -268,7 +514,7 @@ mod tests {
     fn matrix_inequality() {
         let m1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
         let m2 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.5]);
-        assert!(m1.equals(&m2) == false);
+        assert!(!m1.equals(&m2));
     }
 
     #[test]
@@ -320,6 +566,34 @@ mod tests {
         assert!(result.equals(&expect));
     }
 
+    #[test]
+    fn multiplication_non_square() {
+        let m1 = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let m2 = Matrix::new(2, 3, vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0]);
+
+        let result = &m1 * &m2;
+        let expect = Matrix::new(2, 2, vec![58.0, 64.0, 139.0, 154.0]);
+        assert!(result.equals(&expect));
+
+        let result = &m2 * &m1;
+        let expect = Matrix::new(
+            3,
+            3,
+            vec![39.0, 54.0, 69.0, 49.0, 68.0, 87.0, 59.0, 82.0, 105.0],
+        );
+        assert!(result.equals(&expect));
+    }
+
+    #[test]
+    fn multiplication_row_vector() {
+        let m1 = Matrix::new(3, 1, vec![1.0, 2.0, 3.0]);
+        let m2 = Matrix::new(2, 3, vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+
+        let result = &m1 * &m2;
+        let expect = Matrix::new(2, 1, vec![4.0, 5.0]);
+        assert!(result.equals(&expect));
+    }
+
     #[test]
     fn multiplication_tuple() {
         let m1 = Matrix::new(
@@ -340,7 +614,7 @@ mod tests {
 
     #[test]
     fn create_identity() {
-        let m1 = Matrix::new_identity(4);
+        let m1: Matrix = Matrix::new_identity(4);
         assert!(m1.get(0, 0) == 1.0);
         assert!(m1.get(1, 1) == 1.0);
         assert!(m1.get(2, 2) == 1.0);
@@ -469,11 +743,11 @@ mod tests {
         let cofactor4 = m1.cofactor(0, 3);
         let determinant = m1.determinant();
 
-        assert!(cofactor1 == 690.0);
-        assert!(cofactor2 == 447.0);
-        assert!(cofactor3 == 210.0);
-        assert!(cofactor4 == 51.0);
-        assert!(determinant == -4071.0);
+        assert!(equal(cofactor1, 690.0));
+        assert!(equal(cofactor2, 447.0));
+        assert!(equal(cofactor3, 210.0));
+        assert!(equal(cofactor4, 51.0));
+        assert!(equal(determinant, -4071.0));
     }
 
     #[test]
@@ -485,7 +759,7 @@ mod tests {
                 6.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 6.0, 4.0, -9.0, 3.0, -7.0, 9.0, 1.0, 7.0, -6.0,
             ],
         );
-        assert!(m1.is_invertable() == true);
+        assert!(m1.is_invertable());
     }
 
     #[test]
@@ -497,7 +771,7 @@ mod tests {
                 -4.0, 2.0, -2.0, -3.0, 9.0, 6.0, 2.0, 6.0, 0.0, -5.0, 1.0, -5.0, 0.0, 0.0, 0.0, 0.0,
             ],
         );
-        assert!(m1.is_invertable() == false);
+        assert!(!m1.is_invertable());
     }
 
     #[test]
@@ -521,7 +795,7 @@ mod tests {
 
         let inverse = m1.inverse().expect("failed to inverse");
 
-        assert!(inverse.equals(&expected) == true);
+        assert!(inverse.equals(&expected));
     }
 
     #[test]
@@ -546,7 +820,7 @@ mod tests {
 
         let inverse = m1.inverse().expect("failed to inverse");
 
-        assert!(inverse.equals(&expected) == true);
+        assert!(inverse.equals(&expected));
     }
 
     #[test]
@@ -572,6 +846,182 @@ mod tests {
 
         let m4 = &m3 * &m2.inverse().expect("m2 didn't get invsersed");
 
-        assert!(m4.equals(&m1) == true);
+        assert!(m4.equals(&m1));
+    }
+
+    #[test]
+    fn determinant_5x5() {
+        let m1 = Matrix::new(
+            5,
+            5,
+            vec![
+                2.0, 0.0, 1.0, 3.0, 4.0, 1.0, 1.0, 0.0, 2.0, 1.0, 0.0, 3.0, 1.0, 1.0, 0.0, 4.0,
+                1.0, 2.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 2.0,
+            ],
+        );
+
+        let mut expect = 0.0;
+        for index in 0..5 {
+            expect += m1.get(0, index) * m1.cofactor(0, index);
+        }
+
+        assert!(equal(m1.determinant(), expect));
+    }
+
+    #[test]
+    fn determinant_needs_pivoting() {
+        let m1 = Matrix::new(3, 3, vec![0.0, 1.0, 2.0, 1.0, 0.0, 3.0, 4.0, -3.0, 8.0]);
+        assert!(equal(m1.determinant(), -2.0));
+    }
+
+    #[test]
+    fn inverse_5x5() {
+        let m1 = Matrix::new(
+            5,
+            5,
+            vec![
+                0.0, 2.0, 1.0, 3.0, 4.0, 1.0, 1.0, 0.0, 2.0, 1.0, 0.0, 3.0, 1.0, 1.0, 0.0, 4.0,
+                1.0, 2.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 2.0,
+            ],
+        );
+
+        let inverse = m1.inverse().expect("failed to inverse");
+        assert!((&m1 * &inverse).equals(&Matrix::new_identity(5)));
+        assert!((&inverse * &m1).equals(&Matrix::new_identity(5)));
+    }
+
+    #[test]
+    fn inverse_singular() {
+        let m1 = Matrix::new(3, 3, vec![1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 1.0, 0.0, 1.0]);
+        assert!(m1.inverse().is_none());
+    }
+
+    #[test]
+    fn solve_linear_system() {
+        // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+        let a = Matrix::new(3, 3, vec![2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0]);
+        let b = Matrix::new(1, 3, vec![8.0, -11.0, -3.0]);
+
+        let result = a.solve(&b).expect("system has no unique solution");
+        let expect = Matrix::new(1, 3, vec![2.0, 3.0, -1.0]);
+        assert!(result.equals(&expect));
+        assert!((&a * &result).equals(&b));
+    }
+
+    #[test]
+    fn solve_singular_system() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        let b = Matrix::new(1, 2, vec![1.0, 2.0]);
+        assert!(a.solve(&b).is_none());
+    }
+
+    #[test]
+    fn add_and_subtract() {
+        let m1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let m2 = Matrix::new(2, 2, vec![4.0, 3.0, 2.0, 1.0]);
+
+        assert_eq!(&m1 + &m2, Matrix::new(2, 2, vec![5.0, 5.0, 5.0, 5.0]));
+        assert_eq!(&m1 - &m2, Matrix::new(2, 2, vec![-3.0, -1.0, 1.0, 3.0]));
+        assert_eq!(-m1.clone(), Matrix::new(2, 2, vec![-1.0, -2.0, -3.0, -4.0]));
+
+        let mut m3 = m1.clone();
+        m3 += &m2;
+        m3 -= &m1;
+        assert_eq!(m3, m2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn add_size_mismatch() {
+        let m1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let m2 = Matrix::new_identity(3);
+        let _ = m1 + m2;
+    }
+
+    #[test]
+    fn owned_multiplication() {
+        let m1 = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let m2 = Matrix::new(2, 2, vec![0.0, 1.0, 1.0, 0.0]);
+        let expect = Matrix::new(2, 2, vec![2.0, 1.0, 4.0, 3.0]);
+
+        assert_eq!(m1.clone() * m2.clone(), expect);
+        assert_eq!(&m1 * m2.clone(), expect);
+
+        let mut m3 = m1.clone();
+        m3 *= &m2;
+        assert_eq!(m3, expect);
+
+        let mut m4 = m1 * 2.0;
+        m4 *= 0.5;
+        assert_eq!(m4, Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn index() {
+        let mut m1 = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(m1[(1, 0)], 4.0);
+        assert_eq!(m1[(0, 2)], m1.get(0, 2));
+
+        m1[(1, 2)] = 9.0;
+        assert_eq!(m1.get(1, 2), 9.0);
+    }
+
+    #[test]
+    fn near_singular() {
+        let m1 = Matrix::new(2, 2, vec![1.0, 2.0, 1.0, 2.0 + 1e-14]);
+        assert!(m1.determinant() != 0.0);
+        assert!(!m1.is_invertable());
+        assert!(m1.inverse().is_none());
+
+        let m2 = Matrix::new(2, 2, vec![1e-6, 0.0, 0.0, 1e-6]);
+        assert!(m2.is_invertable());
+        assert!(m2.is_near_singular(2.0));
+    }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let m1 = Matrix::new(2, 1, vec![1e8, 1.0]);
+        let m2 = Matrix::new(2, 1, vec![1e8 + 1.0, 1.0]);
+        assert!(!m1.equals(&m2));
+        assert!(m1.approx_eq(
+            &m2,
+            Tolerance::Relative {
+                absolute: 1e-9,
+                relative: 1e-6
+            }
+        ));
+        assert!(!m1.approx_eq(&Matrix::new(1, 2, vec![1e8, 1.0]), Tolerance::Absolute(1.0)));
+    }
+
+    #[test]
+    fn single_precision() {
+        let m1: Matrix<f32> =
+            Matrix::new(3, 3, vec![2.0, 1.0, -1.0, -3.0, -1.0, 2.0, -2.0, 1.0, 2.0]);
+        let b = Matrix::new(1, 3, vec![8.0, -11.0, -3.0]);
+
+        let result = m1.solve(&b).expect("system has no unique solution");
+        assert_eq!(result, Matrix::new(1, 3, vec![2.0, 3.0, -1.0]));
+        assert_eq!(
+            result.cast::<f64>(),
+            Matrix::new(1, 3, vec![2.0, 3.0, -1.0])
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let matrix = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert_eq!(json, "[[1.0,2.0,3.0],[4.0,5.0,6.0]]");
+
+        let result: Matrix = serde_json::from_str(&json).unwrap();
+        assert_eq!((result.width, result.height), (3, 2));
+        assert_eq!(result, matrix);
+
+        let error = serde_json::from_str::<Matrix>("[[1.0,2.0],[3.0]]").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("row 1 has 1 values, expected 2"));
     }
 }
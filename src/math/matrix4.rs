@@ -0,0 +1,576 @@
+use super::{
+    compare::{self, ApproxEq, Tolerance},
+    matrix::Matrix,
+    ops::forward_ref_binop,
+    point::Point3,
+    scalar::Scalar,
+    tuple::Tuple,
+    vector::Vector3,
+};
+
+#[derive(Debug, Clone, Copy)]
+// written as an array of rows
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        transparent,
+        bound(
+            serialize = "T: Scalar + serde::Serialize",
+            deserialize = "T: Scalar + serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Matrix4<T = f64> {
+    pub data: [[T; 4]; 4],
+}
+
+impl<T: Scalar> std::ops::Mul for Matrix4<T> {
+    type Output = Matrix4<T>;
+
+    fn mul(self, rhs: Matrix4<T>) -> Self::Output {
+        Matrix4::new(T::mul_matrix(&self.data, &rhs.data))
+    }
+}
+
+impl<T: Scalar> std::ops::Mul<Tuple<T>> for Matrix4<T> {
+    type Output = Tuple<T>;
+
+    fn mul(self, rhs: Tuple<T>) -> Self::Output {
+        Tuple::from(T::mul_matrix_tuple(&self.data, &rhs.to_array()))
+    }
+}
+
+// points pick up the translation column, vectors ignore it
+impl<T: Scalar> std::ops::Mul<Point3<T>> for Matrix4<T> {
+    type Output = Point3<T>;
+
+    fn mul(self, rhs: Point3<T>) -> Self::Output {
+        let [x, y, z, _] = T::mul_matrix_tuple(&self.data, &[rhs.x, rhs.y, rhs.z, T::ONE]);
+        Point3::new(x, y, z)
+    }
+}
+
+impl<T: Scalar> std::ops::Mul<Vector3<T>> for Matrix4<T> {
+    type Output = Vector3<T>;
+
+    fn mul(self, rhs: Vector3<T>) -> Self::Output {
+        let [x, y, z, _] = T::mul_matrix_tuple(&self.data, &rhs.to_array());
+        Vector3::new(x, y, z)
+    }
+}
+
+forward_ref_binop!(impl Mul, mul for Matrix4<T>, Matrix4<T>);
+forward_ref_binop!(impl Mul, mul for Matrix4<T>, Tuple<T>);
+forward_ref_binop!(impl Mul, mul for Matrix4<T>, Point3<T>);
+forward_ref_binop!(impl Mul, mul for Matrix4<T>, Vector3<T>);
+
+impl<T: Scalar> std::ops::MulAssign for Matrix4<T> {
+    fn mul_assign(&mut self, rhs: Matrix4<T>) {
+        *self = *self * rhs;
+    }
+}
+
+// indexed as (row, column), the same order as get
+impl<T: Scalar> std::ops::Index<(usize, usize)> for Matrix4<T> {
+    type Output = T;
+
+    fn index(&self, (y, x): (usize, usize)) -> &Self::Output {
+        &self.data[y][x]
+    }
+}
+
+impl<T: Scalar> std::ops::IndexMut<(usize, usize)> for Matrix4<T> {
+    fn index_mut(&mut self, (y, x): (usize, usize)) -> &mut Self::Output {
+        &mut self.data[y][x]
+    }
+}
+
+impl<T: Scalar> ApproxEq for Matrix4<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        self.data
+            .iter()
+            .flatten()
+            .zip(other.data.iter().flatten())
+            .all(|(a, b)| tolerance.equal(*a, *b))
+    }
+}
+
+impl<T: Scalar> PartialEq for Matrix4<T> {
+    fn eq(&self, other: &Matrix4<T>) -> bool {
+        self.equals(other)
+    }
+}
+
+impl<T: Scalar> From<Matrix4<T>> for Matrix<T> {
+    fn from(matrix: Matrix4<T>) -> Self {
+        Matrix::new(4, 4, matrix.data.concat())
+    }
+}
+
+impl<T: Scalar> TryFrom<&Matrix<T>> for Matrix4<T> {
+    type Error = (usize, usize);
+
+    fn try_from(matrix: &Matrix<T>) -> Result<Self, Self::Error> {
+        if matrix.width != 4 || matrix.height != 4 {
+            return Err((matrix.width, matrix.height));
+        }
+
+        let mut data = [[T::ZERO; 4]; 4];
+        for (y, row) in data.iter_mut().enumerate() {
+            row.copy_from_slice(&matrix.data[y * 4..y * 4 + 4]);
+        }
+
+        Ok(Matrix4 { data })
+    }
+}
+
+impl<T: Scalar> Matrix4<T> {
+    pub fn new(data: [[T; 4]; 4]) -> Matrix4<T> {
+        Matrix4 { data }
+    }
+
+    pub fn new_identity() -> Matrix4<T> {
+        Matrix4 {
+            data: [
+                [T::ONE, T::ZERO, T::ZERO, T::ZERO],
+                [T::ZERO, T::ONE, T::ZERO, T::ZERO],
+                [T::ZERO, T::ZERO, T::ONE, T::ZERO],
+                [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+            ],
+        }
+    }
+
+    pub fn get(&self, y: usize, x: usize) -> T {
+        self.data[y][x]
+    }
+
+    pub fn equals(&self, other: &Matrix4<T>) -> bool {
+        self.approx_eq(other, Tolerance::default())
+    }
+
+    pub fn transpose(&self) -> Matrix4<T> {
+        let mut data = [[T::ZERO; 4]; 4];
+
+        for (y, row) in data.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value = self.data[x][y];
+            }
+        }
+
+        Matrix4 { data }
+    }
+
+    pub fn determinant(&self) -> T {
+        let (s, c) = self.minors_2x2();
+        determinant_from_minors(&s, &c)
+    }
+
+    pub fn is_invertable(&self) -> bool {
+        !self.is_near_singular(T::SINGULAR_EPSILON)
+    }
+
+    pub fn is_near_singular(&self, epsilon: f64) -> bool {
+        compare::near_singular(self.determinant().to_f64(), self.row_lengths(), epsilon)
+    }
+
+    pub fn inverse(&self) -> Option<Matrix4<T>> {
+        let (s, c) = self.minors_2x2();
+        let determinant = determinant_from_minors(&s, &c);
+
+        if compare::near_singular(
+            determinant.to_f64(),
+            self.row_lengths(),
+            T::SINGULAR_EPSILON,
+        ) {
+            return None;
+        }
+
+        let m = &self.data;
+        let scale = T::ONE / determinant;
+
+        Some(Matrix4 {
+            data: [
+                [
+                    (m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3]) * scale,
+                    (-m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3]) * scale,
+                    (m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3]) * scale,
+                    (-m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3]) * scale,
+                ],
+                [
+                    (-m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1]) * scale,
+                    (m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1]) * scale,
+                    (-m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1]) * scale,
+                    (m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1]) * scale,
+                ],
+                [
+                    (m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0]) * scale,
+                    (-m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0]) * scale,
+                    (m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0]) * scale,
+                    (-m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0]) * scale,
+                ],
+                [
+                    (-m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0]) * scale,
+                    (m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0]) * scale,
+                    (-m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0]) * scale,
+                    (m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0]) * scale,
+                ],
+            ],
+        })
+    }
+
+    pub fn cast<U: Scalar>(&self) -> Matrix4<U> {
+        Matrix4::new(
+            self.data
+                .map(|row| row.map(|value| U::from_f64(value.to_f64()))),
+        )
+    }
+
+    fn row_lengths(&self) -> impl Iterator<Item = f64> + '_ {
+        self.data.iter().map(|row| {
+            row.iter()
+                .map(|value| value.to_f64().powi(2))
+                .sum::<f64>()
+                .sqrt()
+        })
+    }
+
+    // the 2x2 determinants of the top two rows (s) and bottom two rows (c),
+    // which the determinant and the adjugate are both built from
+    fn minors_2x2(&self) -> ([T; 6], [T; 6]) {
+        let m = &self.data;
+
+        let s = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+
+        let c = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+
+        (s, c)
+    }
+}
+
+fn determinant_from_minors<T: Scalar>(s: &[T; 6], c: &[T; 6]) -> T {
+    s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::compare::equal;
+    use super::*;
+
+    #[test]
+    fn create_matrix() {
+        let matrix = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.5, 6.5, 7.5, 8.5],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.5, 14.5, 15.5, 16.5],
+        ]);
+
+        assert!(matrix.get(0, 0) == 1.0);
+        assert!(matrix.get(0, 3) == 4.0);
+        assert!(matrix.get(1, 0) == 5.5);
+        assert!(matrix.get(1, 2) == 7.5);
+        assert!(matrix.get(3, 2) == 15.5);
+    }
+
+    #[test]
+    fn multiplication() {
+        let m1 = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 8.0, 7.0, 6.0],
+            [5.0, 4.0, 3.0, 2.0],
+        ]);
+        let m2 = Matrix4::new([
+            [-2.0, 1.0, 2.0, 3.0],
+            [3.0, 2.0, 1.0, -1.0],
+            [4.0, 3.0, 6.0, 5.0],
+            [1.0, 2.0, 7.0, 8.0],
+        ]);
+
+        let expect = Matrix4::new([
+            [20.0, 22.0, 50.0, 48.0],
+            [44.0, 54.0, 114.0, 108.0],
+            [40.0, 58.0, 110.0, 102.0],
+            [16.0, 26.0, 46.0, 42.0],
+        ]);
+
+        assert!((m1 * m2).equals(&expect));
+    }
+
+    #[test]
+    fn multiplication_tuple() {
+        let m1 = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 4.0, 2.0],
+            [8.0, 6.0, 4.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let result = m1 * Tuple::new_tuple(1.0, 2.0, 3.0, 1.0);
+        let expect = Tuple::new_tuple(18.0, 24.0, 33.0, 1.0);
+
+        assert!(result.equals(&expect));
+    }
+
+    #[test]
+    fn multiplication_point_and_vector() {
+        let m1 = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 4.0, 2.0],
+            [8.0, 6.0, 4.0, 1.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let point = m1 * Point3::new(1.0, 2.0, 3.0);
+        assert!(point.equals(&Point3::new(18.0, 24.0, 33.0)));
+
+        let vector = m1 * Vector3::new(1.0, 2.0, 3.0);
+        assert!(vector.equals(&Vector3::new(14.0, 22.0, 32.0)));
+    }
+
+    #[test]
+    fn identity_multiplication() {
+        let m1 = Matrix4::new([
+            [0.0, 1.0, 2.0, 4.0],
+            [1.0, 2.0, 4.0, 8.0],
+            [2.0, 4.0, 8.0, 16.0],
+            [4.0, 8.0, 16.0, 32.0],
+        ]);
+
+        assert!((m1 * Matrix4::new_identity()).equals(&m1));
+    }
+
+    #[test]
+    fn transpose() {
+        let m1 = Matrix4::new([
+            [0.0, 9.0, 3.0, 0.0],
+            [9.0, 8.0, 0.0, 8.0],
+            [1.0, 8.0, 5.0, 3.0],
+            [0.0, 0.0, 5.0, 8.0],
+        ]);
+        let expect = Matrix4::new([
+            [0.0, 9.0, 1.0, 0.0],
+            [9.0, 8.0, 8.0, 0.0],
+            [3.0, 0.0, 5.0, 5.0],
+            [0.0, 8.0, 3.0, 8.0],
+        ]);
+
+        assert!(m1.transpose().equals(&expect));
+        let identity: Matrix4 = Matrix4::new_identity();
+        assert!(identity.transpose().equals(&identity));
+    }
+
+    #[test]
+    fn determinant() {
+        let m1 = Matrix4::new([
+            [-2.0, -8.0, 3.0, 5.0],
+            [-3.0, 1.0, 7.0, 3.0],
+            [1.0, 2.0, -9.0, 6.0],
+            [-6.0, 7.0, 7.0, -9.0],
+        ]);
+
+        assert!(m1.determinant() == -4071.0);
+        assert!(equal(m1.determinant(), Matrix::from(m1).determinant()));
+    }
+
+    #[test]
+    fn is_not_invertable() {
+        let m1 = Matrix4::new([
+            [-4.0, 2.0, -2.0, -3.0],
+            [9.0, 6.0, 2.0, 6.0],
+            [0.0, -5.0, 1.0, -5.0],
+            [0.0, 0.0, 0.0, 0.0],
+        ]);
+
+        assert!(!m1.is_invertable());
+        assert!(m1.inverse().is_none());
+    }
+
+    #[test]
+    fn inverse_1() {
+        let m1 = Matrix4::new([
+            [-5.0, 2.0, 6.0, -8.0],
+            [1.0, -5.0, 1.0, 8.0],
+            [7.0, 7.0, -6.0, -7.0],
+            [1.0, -3.0, 7.0, 4.0],
+        ]);
+        let expect = Matrix4::new([
+            [0.21805, 0.45113, 0.24060, -0.04511],
+            [-0.80827, -1.45677, -0.44361, 0.52068],
+            [-0.07895, -0.22368, -0.05263, 0.19737],
+            [-0.52256, -0.81391, -0.30075, 0.30639],
+        ]);
+
+        let inverse = m1.inverse().expect("failed to inverse");
+        assert!(m1.determinant() == 532.0);
+        assert!(inverse.equals(&expect));
+    }
+
+    #[test]
+    fn inverse_2() {
+        let m1 = Matrix4::new([
+            [9.0, 3.0, 0.0, 9.0],
+            [-5.0, -2.0, -6.0, -3.0],
+            [-4.0, 9.0, 6.0, 4.0],
+            [-7.0, 6.0, 6.0, 2.0],
+        ]);
+        let expect = Matrix4::new([
+            [-0.04074, -0.07778, 0.14444, -0.22222],
+            [-0.07778, 0.03333, 0.36667, -0.33333],
+            [-0.02901, -0.14630, -0.10926, 0.12963],
+            [0.17778, 0.06667, -0.26667, 0.33333],
+        ]);
+
+        let inverse = m1.inverse().expect("failed to inverse");
+        assert!(inverse.equals(&expect));
+    }
+
+    #[test]
+    fn inverse_matches_matrix() {
+        let m1 = Matrix4::new([
+            [8.0, -5.0, 9.0, 2.0],
+            [7.0, 5.0, 6.0, 1.0],
+            [-6.0, 0.0, 9.0, 6.0],
+            [-3.0, 0.0, -9.0, -4.0],
+        ]);
+
+        let expect = Matrix::from(m1).inverse().expect("failed to inverse");
+        let inverse = Matrix::from(m1.inverse().expect("failed to inverse"));
+        assert!(inverse.equals(&expect));
+    }
+
+    #[test]
+    fn multiply_by_inverse() {
+        let m1 = Matrix4::new([
+            [3.0, -9.0, 7.0, 3.0],
+            [3.0, -8.0, 2.0, -9.0],
+            [-4.0, 4.0, 4.0, 1.0],
+            [-6.0, 5.0, -1.0, 1.0],
+        ]);
+        let m2 = Matrix4::new([
+            [8.0, 2.0, 2.0, 2.0],
+            [3.0, -1.0, 7.0, 0.0],
+            [7.0, 0.0, 5.0, 4.0],
+            [6.0, -2.0, 0.0, 5.0],
+        ]);
+
+        let m3 = m1 * m2;
+        let result = m3 * m2.inverse().expect("m2 didn't get inversed");
+
+        assert!(result.equals(&m1));
+    }
+
+    #[test]
+    fn convert_from_matrix() {
+        let matrix: Matrix = Matrix::new_identity(4);
+        let result = Matrix4::try_from(&matrix).expect("not a 4x4 matrix");
+        assert!(result.equals(&Matrix4::new_identity()));
+        assert!(Matrix4::try_from(&Matrix::<f64>::new_identity(3)).is_err());
+    }
+
+    #[test]
+    fn index_and_multiply_assign() {
+        let mut m1 = Matrix4::new_identity();
+        m1[(0, 3)] = 5.0;
+        assert_eq!(m1[(0, 3)], 5.0);
+        assert_eq!(m1.get(0, 3), 5.0);
+
+        m1 *= m1;
+        assert_eq!(m1[(0, 3)], 10.0);
+        assert_eq!(m1 * Point3::new_origin(), Point3::new(10.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn near_singular() {
+        let m1 = Matrix4::new([
+            [1.0, 2.0, 3.0, 0.0],
+            [2.0, 4.0, 6.0 + 1e-13, 0.0],
+            [0.0, 1.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert!(m1.determinant() != 0.0);
+        assert!(!m1.is_invertable());
+        assert!(m1.inverse().is_none());
+
+        let tiny = Matrix4::new([
+            [1e-5, 0.0, 0.0, 0.0],
+            [0.0, 1e-5, 0.0, 0.0],
+            [0.0, 0.0, 1e-5, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        assert!(tiny.is_invertable());
+        assert!(tiny.inverse().is_some());
+    }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let mut m1 = Matrix4::new_identity();
+        m1[(0, 3)] = 1e7;
+        let mut m2 = m1;
+        m2[(0, 3)] += 0.5;
+
+        assert!(!m1.equals(&m2));
+        assert!(m1.approx_eq(
+            &m2,
+            Tolerance::Relative {
+                absolute: 1e-9,
+                relative: 1e-6
+            }
+        ));
+        assert!(!m1.approx_eq(
+            &m2,
+            Tolerance::Ulps {
+                absolute: 0.0,
+                ulps: 8
+            }
+        ));
+    }
+
+    #[test]
+    fn single_precision() {
+        let m1: Matrix4<f32> = Matrix4::new([
+            [-5.0, 2.0, 6.0, -8.0],
+            [1.0, -5.0, 1.0, 8.0],
+            [7.0, 7.0, -6.0, -7.0],
+            [1.0, -3.0, 7.0, 4.0],
+        ]);
+
+        assert_eq!(m1.determinant(), 532.0);
+        let inverse = m1.inverse().expect("failed to inverse");
+        assert_eq!(m1 * inverse, Matrix4::new_identity());
+        assert_eq!(m1.cast::<f64>().cast::<f32>(), m1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let matrix = Matrix4::new([
+            [1.0, 2.0, 3.0, 4.0],
+            [5.5, 6.5, 7.5, 8.5],
+            [9.0, 8.0, 7.0, 6.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert_eq!(
+            json,
+            "[[1.0,2.0,3.0,4.0],[5.5,6.5,7.5,8.5],[9.0,8.0,7.0,6.0],[0.0,0.0,0.0,1.0]]"
+        );
+        assert_eq!(serde_json::from_str::<Matrix4>(&json).unwrap(), matrix);
+    }
+}
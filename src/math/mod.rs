@@ -1,4 +1,12 @@
-mod compare;
+pub mod compare;
 pub mod matrix;
+pub mod matrix4;
+mod ops;
+pub mod point;
+pub mod quaternion;
+pub mod scalar;
+#[cfg(all(feature = "simd", target_arch = "x86_64", not(target_feature = "avx")))]
+mod simd;
 pub mod transform;
 pub mod tuple;
+pub mod vector;
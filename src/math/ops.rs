@@ -0,0 +1,75 @@
+// copy types implement an operator by value and get the borrowed forms from
+// forward_ref_*, heap backed types implement it on references and get the
+// owned forms from forward_val_binop. the types are generic over T: Scalar
+
+macro_rules! forward_ref_binop {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<$u> for &$t {
+            type Output = <$t as std::ops::$imp<$u>>::Output;
+
+            fn $method(self, rhs: $u) -> Self::Output {
+                std::ops::$imp::$method(*self, rhs)
+            }
+        }
+
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<&$u> for $t {
+            type Output = <$t as std::ops::$imp<$u>>::Output;
+
+            fn $method(self, rhs: &$u) -> Self::Output {
+                std::ops::$imp::$method(self, *rhs)
+            }
+        }
+
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<&$u> for &$t {
+            type Output = <$t as std::ops::$imp<$u>>::Output;
+
+            fn $method(self, rhs: &$u) -> Self::Output {
+                std::ops::$imp::$method(*self, *rhs)
+            }
+        }
+    };
+}
+
+macro_rules! forward_ref_unop {
+    (impl $imp:ident, $method:ident for $t:ty) => {
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp for &$t {
+            type Output = <$t as std::ops::$imp>::Output;
+
+            fn $method(self) -> Self::Output {
+                std::ops::$imp::$method(*self)
+            }
+        }
+    };
+}
+
+macro_rules! forward_val_binop {
+    (impl $imp:ident, $method:ident for $t:ty, $u:ty) => {
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<$u> for $t {
+            type Output = <&'static $t as std::ops::$imp<&'static $u>>::Output;
+
+            fn $method(self, rhs: $u) -> Self::Output {
+                std::ops::$imp::$method(&self, &rhs)
+            }
+        }
+
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<&$u> for $t {
+            type Output = <&'static $t as std::ops::$imp<&'static $u>>::Output;
+
+            fn $method(self, rhs: &$u) -> Self::Output {
+                std::ops::$imp::$method(&self, rhs)
+            }
+        }
+
+        impl<T: crate::math::scalar::Scalar> std::ops::$imp<$u> for &$t {
+            type Output = <&'static $t as std::ops::$imp<&'static $u>>::Output;
+
+            fn $method(self, rhs: $u) -> Self::Output {
+                std::ops::$imp::$method(self, &rhs)
+            }
+        }
+    };
+}
+
+pub(crate) use forward_ref_binop;
+pub(crate) use forward_ref_unop;
+pub(crate) use forward_val_binop;
@@ -0,0 +1,176 @@
+use super::{
+    compare::{ApproxEq, Tolerance},
+    scalar::Scalar,
+    tuple::Tuple,
+    vector::Vector3,
+};
+
+#[derive(Debug, Clone, Copy)]
+// written as a plain [x, y, z] array
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "[T; 3]",
+        into = "[T; 3]",
+        bound(
+            serialize = "T: Scalar + serde::Serialize",
+            deserialize = "T: Scalar + serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Point3<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Scalar> std::ops::Add<Vector3<T>> for Point3<T> {
+    type Output = Point3<T>;
+    fn add(self, rhs: Vector3<T>) -> Self::Output {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Scalar> std::ops::AddAssign<Vector3<T>> for Point3<T> {
+    fn add_assign(&mut self, rhs: Vector3<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Scalar> std::ops::Sub<Vector3<T>> for Point3<T> {
+    type Output = Point3<T>;
+    fn sub(self, rhs: Vector3<T>) -> Self::Output {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Scalar> std::ops::SubAssign<Vector3<T>> for Point3<T> {
+    fn sub_assign(&mut self, rhs: Vector3<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Scalar> std::ops::Sub for Point3<T> {
+    type Output = Vector3<T>;
+    fn sub(self, rhs: Point3<T>) -> Self::Output {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Scalar> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl<T: Scalar> From<Point3<T>> for [T; 3] {
+    fn from(point: Point3<T>) -> Self {
+        [point.x, point.y, point.z]
+    }
+}
+
+impl<T: Scalar> From<Point3<T>> for Tuple<T> {
+    fn from(point: Point3<T>) -> Self {
+        Tuple::new_point(point.x, point.y, point.z)
+    }
+}
+
+// only tuples with a w of one are points, anything else is handed back
+impl<T: Scalar> TryFrom<Tuple<T>> for Point3<T> {
+    type Error = Tuple<T>;
+
+    fn try_from(tuple: Tuple<T>) -> Result<Self, Self::Error> {
+        if tuple.is_point() {
+            Ok(Point3::new(tuple.x, tuple.y, tuple.z))
+        } else {
+            Err(tuple)
+        }
+    }
+}
+
+impl<T: Scalar> ApproxEq for Point3<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.equal(self.x, other.x)
+            && tolerance.equal(self.y, other.y)
+            && tolerance.equal(self.z, other.z)
+    }
+}
+
+impl<T: Scalar> PartialEq for Point3<T> {
+    fn eq(&self, other: &Point3<T>) -> bool {
+        self.equals(other)
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+
+    pub fn new_origin() -> Point3<T> {
+        Point3::new(T::ZERO, T::ZERO, T::ZERO)
+    }
+
+    pub fn cast<U: Scalar>(&self) -> Point3<U> {
+        Point3::new(
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+        )
+    }
+
+    pub fn equals(&self, other: &Point3<T>) -> bool {
+        self.approx_eq(other, Tolerance::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_plus_vector() {
+        let point = Point3::new(3.0, -2.0, 5.0);
+        let vector = Vector3::new(-2.0, 3.0, 1.0);
+        assert!((point + vector).equals(&Point3::new(1.0, 1.0, 6.0)));
+    }
+
+    #[test]
+    fn point_minus_vector() {
+        let point = Point3::new(3.0, 2.0, 1.0);
+        let vector = Vector3::new(5.0, 6.0, 7.0);
+        assert!((point - vector).equals(&Point3::new(-2.0, -4.0, -6.0)));
+    }
+
+    #[test]
+    fn point_minus_point() {
+        let a = Point3::new(3.0, 2.0, 1.0);
+        let b = Point3::new(5.0, 6.0, 7.0);
+        assert!((a - b).equals(&Vector3::new(-2.0, -4.0, -6.0)));
+    }
+
+    #[test]
+    fn convert_tuple() {
+        let tuple: Tuple = Point3::new(4.3, -4.2, 3.1).into();
+        assert!(tuple.is_point());
+
+        let point = Point3::try_from(tuple).expect("tuple is a point");
+        assert!(point.equals(&Point3::new(4.3, -4.2, 3.1)));
+        assert!(Point3::try_from(Tuple::new_vector(1.0, 2.0, 3.0)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let point = Point3::new(1.0, -2.5, 3.0);
+
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, "[1.0,-2.5,3.0]");
+        assert_eq!(serde_json::from_str::<Point3>(&json).unwrap(), point);
+    }
+}
@@ -0,0 +1,460 @@
+use super::{
+    compare::{ApproxEq, Tolerance},
+    matrix4::Matrix4,
+    vector::Vector3,
+};
+
+#[derive(Debug, Clone, Copy)]
+// written as a plain [w, x, y, z] array
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "[f64; 4]", into = "[f64; 4]")
+)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl std::ops::Add for Quaternion {
+    type Output = Quaternion;
+    fn add(self, rhs: Quaternion) -> Self::Output {
+        Quaternion::new(
+            self.w + rhs.w,
+            self.x + rhs.x,
+            self.y + rhs.y,
+            self.z + rhs.z,
+        )
+    }
+}
+
+impl std::ops::Mul<f64> for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: f64) -> Self::Output {
+        Quaternion::new(self.w * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+// the hamilton product, a * b rotates by b and then by a
+impl std::ops::Mul for Quaternion {
+    type Output = Quaternion;
+    fn mul(self, rhs: Quaternion) -> Self::Output {
+        Quaternion::new(
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        )
+    }
+}
+
+impl std::ops::Neg for Quaternion {
+    type Output = Quaternion;
+    fn neg(self) -> Self::Output {
+        Quaternion::new(-self.w, -self.x, -self.y, -self.z)
+    }
+}
+
+impl From<[f64; 4]> for Quaternion {
+    fn from([w, x, y, z]: [f64; 4]) -> Self {
+        Quaternion::new(w, x, y, z)
+    }
+}
+
+impl From<Quaternion> for [f64; 4] {
+    fn from(quaternion: Quaternion) -> Self {
+        [quaternion.w, quaternion.x, quaternion.y, quaternion.z]
+    }
+}
+
+impl From<Quaternion> for Matrix4 {
+    fn from(quaternion: Quaternion) -> Self {
+        quaternion.to_matrix()
+    }
+}
+
+// q and -q are the same rotation, so both count as equal
+impl ApproxEq for Quaternion {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        let same = |sign: f64| {
+            tolerance.equal(self.w, other.w * sign)
+                && tolerance.equal(self.x, other.x * sign)
+                && tolerance.equal(self.y, other.y * sign)
+                && tolerance.equal(self.z, other.z * sign)
+        };
+
+        same(1.0) || same(-1.0)
+    }
+}
+
+impl PartialEq for Quaternion {
+    fn eq(&self, other: &Quaternion) -> bool {
+        self.equals(other)
+    }
+}
+
+impl Quaternion {
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+
+    pub fn new_identity() -> Quaternion {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    // None when the axis is too short to have a direction
+    pub fn from_axis_angle(axis: &Vector3, rad: f64) -> Option<Quaternion> {
+        let axis = axis.normalize();
+        if !(axis.x.is_finite() && axis.y.is_finite() && axis.z.is_finite()) {
+            return None;
+        }
+
+        let (sin, cos) = (rad / 2.0).sin_cos();
+        Some(Quaternion::new(
+            cos,
+            axis.x * sin,
+            axis.y * sin,
+            axis.z * sin,
+        ))
+    }
+
+    // rotates about x first, then y, then z, the same as
+    // Transform::identity().rotate_x(x).rotate_y(y).rotate_z(z)
+    pub fn from_euler(x: f64, y: f64, z: f64) -> Quaternion {
+        let (sin_x, cos_x) = (x / 2.0).sin_cos();
+        let (sin_y, cos_y) = (y / 2.0).sin_cos();
+        let (sin_z, cos_z) = (z / 2.0).sin_cos();
+
+        Quaternion::new(cos_z, 0.0, 0.0, sin_z)
+            * Quaternion::new(cos_y, 0.0, sin_y, 0.0)
+            * Quaternion::new(cos_x, sin_x, 0.0, 0.0)
+    }
+
+    // the matrix must be a pure rotation, see Matrix4::decompose otherwise
+    pub fn from_matrix(matrix: &Matrix4) -> Quaternion {
+        let m = &matrix.data;
+        let trace = m[0][0] + m[1][1] + m[2][2];
+
+        let quaternion = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quaternion::new(
+                s / 4.0,
+                (m[2][1] - m[1][2]) / s,
+                (m[0][2] - m[2][0]) / s,
+                (m[1][0] - m[0][1]) / s,
+            )
+        } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+            let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[2][1] - m[1][2]) / s,
+                s / 4.0,
+                (m[0][1] + m[1][0]) / s,
+                (m[0][2] + m[2][0]) / s,
+            )
+        } else if m[1][1] > m[2][2] {
+            let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[0][2] - m[2][0]) / s,
+                (m[0][1] + m[1][0]) / s,
+                s / 4.0,
+                (m[1][2] + m[2][1]) / s,
+            )
+        } else {
+            let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+            Quaternion::new(
+                (m[1][0] - m[0][1]) / s,
+                (m[0][2] + m[2][0]) / s,
+                (m[1][2] + m[2][1]) / s,
+                s / 4.0,
+            )
+        };
+
+        quaternion.normalize()
+    }
+
+    pub fn to_matrix(&self) -> Matrix4 {
+        let Quaternion { w, x, y, z } = self.normalize();
+
+        Matrix4::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    // the inverse of from_euler, y stays within -pi/2 to pi/2 and at the
+    // poles all of the rotation about z is folded into x
+    pub fn to_euler(&self) -> (f64, f64, f64) {
+        let m = self.to_matrix().data;
+        let sin_y = (-m[2][0]).clamp(-1.0, 1.0);
+        let cos_y = m[0][0].hypot(m[1][0]);
+        let y = sin_y.atan2(cos_y);
+
+        // the atan2s below stay accurate until cos y all but vanishes, so
+        // only an exact pole loses z
+        if cos_y < 1e-12 {
+            let x = (m[0][1] * sin_y).atan2(m[1][1]);
+            (x, y, 0.0)
+        } else {
+            let x = m[2][1].atan2(m[2][2]);
+            let z = m[1][0].atan2(m[0][0]);
+            (x, y, z)
+        }
+    }
+
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    pub fn dot(&self, other: &Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn magnitude(&self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Quaternion {
+        *self * (1.0 / self.magnitude())
+    }
+
+    pub fn rotate(&self, vector: &Vector3) -> Vector3 {
+        let rotated = *self * Quaternion::new(0.0, vector.x, vector.y, vector.z) * self.conjugate();
+        Vector3::new(rotated.x, rotated.y, rotated.z)
+    }
+
+    // spherical interpolation along the shorter arc, t runs from 0 (self)
+    // to 1 (other)
+    pub fn slerp(&self, other: &Quaternion, t: f64) -> Quaternion {
+        let mut other = *other;
+        let mut cos = self.dot(&other);
+        if cos < 0.0 {
+            other = -other;
+            cos = -cos;
+        }
+
+        // nearly parallel, where sin of the angle is too small to divide by
+        if cos > 0.9995 {
+            return (*self * (1.0 - t) + other * t).normalize();
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+        let from = ((1.0 - t) * angle).sin() / sin;
+        let to = (t * angle).sin() / sin;
+
+        *self * from + other * to
+    }
+
+    pub fn equals(&self, other: &Quaternion) -> bool {
+        self.approx_eq(other, Tolerance::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::super::{compare, point::Point3, transform::transform};
+    use super::*;
+
+    #[test]
+    fn identity() {
+        let vector = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(Quaternion::new_identity().rotate(&vector), vector);
+        assert_eq!(
+            Quaternion::new_identity().to_matrix(),
+            Matrix4::new_identity()
+        );
+    }
+
+    #[test]
+    fn axis_angle_matches_rotation_matrices() {
+        let x = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), PI / 3.0)
+            .expect("axis is not zero");
+        let y = Quaternion::from_axis_angle(&Vector3::new(0.0, 2.0, 0.0), PI / 4.0)
+            .expect("axis is not zero");
+        let z = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), -PI / 5.0)
+            .expect("axis is not zero");
+
+        assert_eq!(x.to_matrix(), transform::new_rotation_x(PI / 3.0));
+        assert_eq!(y.to_matrix(), transform::new_rotation_y(PI / 4.0));
+        assert_eq!(z.to_matrix(), transform::new_rotation_z(-PI / 5.0));
+    }
+
+    #[test]
+    fn axis_angle_zero_axis() {
+        assert!(Quaternion::from_axis_angle(&Vector3::new_zero(), PI / 3.0).is_none());
+        assert!(Quaternion::from_axis_angle(&Vector3::new(0.0, 1e-200, 0.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn rotate_vector() {
+        let quaternion = Quaternion::from_axis_angle(&Vector3::new(0.0, 0.0, 1.0), PI / 2.0)
+            .expect("axis is not zero");
+        let result = quaternion.rotate(&Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(result, Vector3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn product_composes_rotations() {
+        let a = Quaternion::from_axis_angle(&Vector3::new(1.0, 0.0, 0.0), PI / 2.0)
+            .expect("axis is not zero");
+        let b = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), PI / 3.0)
+            .expect("axis is not zero");
+
+        let expect = transform::new_rotation_y(PI / 3.0) * transform::new_rotation_x(PI / 2.0);
+        assert_eq!((b * a).to_matrix(), expect);
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let axes = [
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, -1.0, 0.0),
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(-0.3, 0.1, 0.9),
+        ];
+
+        for axis in axes {
+            for rad in [0.0, 0.5, PI / 2.0, 2.0, PI, 4.0] {
+                let quaternion = Quaternion::from_axis_angle(&axis, rad).expect("axis is not zero");
+                let result = Quaternion::from_matrix(&quaternion.to_matrix());
+                assert_eq!(result, quaternion);
+            }
+        }
+    }
+
+    #[test]
+    fn negated_is_same_rotation() {
+        let quaternion = Quaternion::from_axis_angle(&Vector3::new(1.0, 1.0, 0.0), 1.0)
+            .expect("axis is not zero");
+        assert_eq!(-quaternion, quaternion);
+        assert_eq!((-quaternion).to_matrix(), quaternion.to_matrix());
+    }
+
+    #[test]
+    fn euler_matches_transforms() {
+        let quaternion = Quaternion::from_euler(0.3, -0.7, 1.2);
+        let expect = transform::new_rotation_z(1.2)
+            * transform::new_rotation_y(-0.7)
+            * transform::new_rotation_x(0.3);
+        assert_eq!(quaternion.to_matrix(), expect);
+
+        let (x, y, z) = quaternion.to_euler();
+        assert!(compare::equal(x, 0.3));
+        assert!(compare::equal(y, -0.7));
+        assert!(compare::equal(z, 1.2));
+    }
+
+    #[test]
+    fn euler_at_pole() {
+        let quaternion = Quaternion::from_euler(0.4, PI / 2.0, 0.1);
+        let (x, y, z) = quaternion.to_euler();
+        assert!(compare::equal(y, PI / 2.0));
+        assert_eq!(z, 0.0);
+        assert_eq!(Quaternion::from_euler(x, y, z), quaternion);
+    }
+
+    #[test]
+    fn euler_near_pole() {
+        for y in [PI / 2.0 - 0.01, 1e-6 - PI / 2.0] {
+            let quaternion = Quaternion::from_euler(0.3, y, 0.5);
+            let euler = quaternion.to_euler();
+            assert!((euler.0 - 0.3).abs() < 1e-9);
+            assert!((euler.1 - y).abs() < 1e-9);
+            assert!((euler.2 - 0.5).abs() < 1e-9);
+
+            let point = Vector3::new(1.0, 2.0, 3.0);
+            let round_trip = Quaternion::from_euler(euler.0, euler.1, euler.2);
+            assert!((round_trip.rotate(&point) - quaternion.rotate(&point)).magnitude() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn slerp_end_points_and_middle() {
+        let axis = Vector3::new(0.0, 1.0, 0.0);
+        let a = Quaternion::from_axis_angle(&axis, 0.0).expect("axis is not zero");
+        let b = Quaternion::from_axis_angle(&axis, PI / 2.0).expect("axis is not zero");
+
+        assert_eq!(a.slerp(&b, 0.0), a);
+        assert_eq!(a.slerp(&b, 1.0), b);
+        assert_eq!(
+            a.slerp(&b, 0.5),
+            Quaternion::from_axis_angle(&axis, PI / 4.0).expect("axis is not zero")
+        );
+
+        let middle = a.slerp(&b, 0.25);
+        assert!(compare::equal(middle.magnitude(), 1.0));
+        assert_eq!(
+            middle,
+            Quaternion::from_axis_angle(&axis, PI / 8.0).expect("axis is not zero")
+        );
+    }
+
+    #[test]
+    fn slerp_takes_shorter_arc() {
+        let axis = Vector3::new(0.0, 0.0, 1.0);
+        let a = Quaternion::from_axis_angle(&axis, 0.1).expect("axis is not zero");
+        let b = -Quaternion::from_axis_angle(&axis, 0.3).expect("axis is not zero");
+        assert_eq!(
+            a.slerp(&b, 0.5),
+            Quaternion::from_axis_angle(&axis, 0.2).expect("axis is not zero")
+        );
+    }
+
+    #[test]
+    fn slerp_nearly_parallel() {
+        let axis = Vector3::new(1.0, 0.0, 0.0);
+        let a = Quaternion::from_axis_angle(&axis, 0.0).expect("axis is not zero");
+        let b = Quaternion::from_axis_angle(&axis, 0.001).expect("axis is not zero");
+        let result = a.slerp(&b, 0.5);
+        assert!(compare::equal(result.magnitude(), 1.0));
+        assert_eq!(
+            result,
+            Quaternion::from_axis_angle(&axis, 0.0005).expect("axis is not zero")
+        );
+    }
+
+    #[test]
+    fn rotate_point_through_matrix() {
+        let quaternion = Quaternion::from_axis_angle(&Vector3::new(0.0, 1.0, 0.0), PI / 2.0)
+            .expect("axis is not zero");
+        let matrix: Matrix4 = quaternion.into();
+        assert_eq!(
+            matrix * Point3::new(0.0, 0.0, 1.0),
+            Point3::new(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let quaternion = Quaternion::new(0.5, 0.5, -0.5, 0.5);
+
+        let json = serde_json::to_string(&quaternion).unwrap();
+        assert_eq!(json, "[0.5,0.5,-0.5,0.5]");
+        assert_eq!(
+            serde_json::from_str::<Quaternion>(&json).unwrap(),
+            quaternion
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::{
+    cmp::Ordering,
+    fmt::Debug,
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+// the floating point types the math types can be built on, f64 is the
+// default everywhere and f32 halves the memory of large meshes
+pub trait Scalar:
+    Copy
+    + Debug
+    + Default
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+    + Sum
+    + Product
+    + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    // see compare::near_singular
+    const SINGULAR_EPSILON: f64;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn is_finite(self) -> bool;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+    // maps the bits onto integers that sort the same way the values do, so
+    // neighbouring values are one apart
+    fn ordered_bits(self) -> i64;
+
+    // the hot vector and matrix kernels, the simd feature swaps mul_matrix
+    // out for an sse version on x86_64
+    fn dot4(a: &[Self; 4], b: &[Self; 4]) -> Self {
+        a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3]
+    }
+
+    // the w of the result is always zero
+    fn cross3(a: &[Self; 4], b: &[Self; 4]) -> [Self; 4] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+            Self::ZERO,
+        ]
+    }
+
+    fn mul_matrix_tuple(m: &[[Self; 4]; 4], v: &[Self; 4]) -> [Self; 4] {
+        m.map(|row| Self::dot4(&row, v))
+    }
+
+    fn mul_matrix(a: &[[Self; 4]; 4], b: &[[Self; 4]; 4]) -> [[Self; 4]; 4] {
+        let mut data = [[Self::ZERO; 4]; 4];
+
+        for (y, row) in data.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value =
+                    a[y][0] * b[0][x] + a[y][1] * b[1][x] + a[y][2] * b[2][x] + a[y][3] * b[3][x];
+            }
+        }
+
+        data
+    }
+}
+
+macro_rules! impl_scalar {
+    ($t:ty, $bits:ty, $singular:expr, $kernels:ident) => {
+        impl Scalar for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const TWO: Self = 2.0;
+            const SINGULAR_EPSILON: f64 = $singular;
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+
+            fn sin_cos(self) -> (Self, Self) {
+                <$t>::sin_cos(self)
+            }
+
+            fn is_finite(self) -> bool {
+                <$t>::is_finite(self)
+            }
+
+            fn total_cmp(&self, other: &Self) -> Ordering {
+                <$t>::total_cmp(self, other)
+            }
+
+            fn ordered_bits(self) -> i64 {
+                let bits = self.to_bits() as $bits;
+                if bits < 0 {
+                    (<$bits>::MIN - bits) as i64
+                } else {
+                    bits as i64
+                }
+            }
+
+            #[cfg(all(feature = "simd", target_arch = "x86_64", not(target_feature = "avx")))]
+            fn mul_matrix(a: &[[Self; 4]; 4], b: &[[Self; 4]; 4]) -> [[Self; 4]; 4] {
+                super::simd::$kernels::mul_matrix(a, b)
+            }
+        }
+    };
+}
+
+impl_scalar!(f32, i32, 1e-6, single);
+impl_scalar!(f64, i64, super::compare::SINGULAR_EPSILON, double);
@@ -0,0 +1,109 @@
+// sse versions of the 4x4 matrix product, the one Scalar kernel that
+// measures faster than the scalar code (see benches/kernels.rs). sse and sse2
+// are part of the x86_64 baseline so nothing is detected at runtime, and when
+// the build enables avx the compiler vectorizes the scalar code at least as
+// well, so this module is left out. sums are added in the same order as the
+// scalar code so results match it exactly
+
+pub(crate) mod double {
+    use std::arch::x86_64::*;
+
+    pub fn mul_matrix(a: &[[f64; 4]; 4], b: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+        let mut data = [[0.0; 4]; 4];
+
+        // safe, sse2 is part of the x86_64 baseline
+        unsafe {
+            for (row, out) in a.iter().zip(data.iter_mut()) {
+                for half in [0, 2] {
+                    let columns = b.map(|row| _mm_loadu_pd(row[half..].as_ptr()));
+                    let mut sum = _mm_mul_pd(_mm_set1_pd(row[0]), columns[0]);
+                    for k in 1..4 {
+                        sum = _mm_add_pd(sum, _mm_mul_pd(_mm_set1_pd(row[k]), columns[k]));
+                    }
+                    _mm_storeu_pd(out[half..].as_mut_ptr(), sum);
+                }
+            }
+        }
+
+        data
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::tests::{expect_product, MATRIX_A, MATRIX_B};
+        use super::*;
+
+        #[test]
+        fn matrix_matrix() {
+            assert_eq!(
+                mul_matrix(&MATRIX_A, &MATRIX_B),
+                expect_product(MATRIX_A, MATRIX_B)
+            );
+        }
+    }
+}
+
+pub(crate) mod single {
+    use std::arch::x86_64::*;
+
+    pub fn mul_matrix(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
+        let mut data = [[0.0; 4]; 4];
+
+        // safe, sse is part of the x86_64 baseline
+        unsafe {
+            let b = b.map(|row| _mm_loadu_ps(row.as_ptr()));
+            for (row, out) in a.iter().zip(data.iter_mut()) {
+                let mut sum = _mm_mul_ps(_mm_set1_ps(row[0]), b[0]);
+                for k in 1..4 {
+                    sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(row[k]), b[k]));
+                }
+                _mm_storeu_ps(out.as_mut_ptr(), sum);
+            }
+        }
+
+        data
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::tests::{expect_product, MATRIX_A, MATRIX_B};
+        use super::*;
+
+        #[test]
+        fn matrix_matrix() {
+            let cast = |value: [f64; 4]| value.map(|value| value as f32);
+            let (a, b) = (MATRIX_A.map(cast), MATRIX_B.map(cast));
+            assert_eq!(mul_matrix(&a, &b), expect_product(a, b));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::scalar::Scalar;
+
+    pub const MATRIX_A: [[f64; 4]; 4] = [
+        [1.0, 2.5, -3.0, 4.0],
+        [0.1, 6.0, 7.25, -8.0],
+        [9.0, -8.5, 7.0, 6.0],
+        [5.0, 4.0, 3.3, 2.0],
+    ];
+    pub const MATRIX_B: [[f64; 4]; 4] = [
+        [-2.0, 1.0, 2.0, 3.0],
+        [3.0, 2.2, 1.0, -1.0],
+        [4.0, 3.0, 6.5, 5.0],
+        [1.0, -2.0, 7.0, 8.125],
+    ];
+
+    // the Scalar default, which the impls override
+    pub fn expect_product<T: Scalar>(a: [[T; 4]; 4], b: [[T; 4]; 4]) -> [[T; 4]; 4] {
+        let mut expect = [[T::ZERO; 4]; 4];
+        for (y, row) in expect.iter_mut().enumerate() {
+            for (x, value) in row.iter_mut().enumerate() {
+                *value =
+                    a[y][0] * b[0][x] + a[y][1] * b[1][x] + a[y][2] * b[2][x] + a[y][3] * b[3][x];
+            }
+        }
+        expect
+    }
+}
@@ -0,0 +1,181 @@
+use super::{
+    super::{matrix4::Matrix4, quaternion::Quaternion, scalar::Scalar, vector::Vector3},
+    transform,
+};
+
+// each step is applied after the ones before it, so
+// Transform::identity().rotate_x(a).scale(x, y, z).translate(x, y, z)
+// builds translation * scale * rotation
+#[derive(Debug, Clone, Copy)]
+pub struct Transform<T = f64> {
+    matrix: Matrix4<T>,
+}
+
+impl<T: Scalar> Transform<T> {
+    pub fn identity() -> Transform<T> {
+        Transform {
+            matrix: Matrix4::new_identity(),
+        }
+    }
+
+    pub fn then(self, matrix: Matrix4<T>) -> Transform<T> {
+        Transform {
+            matrix: matrix * self.matrix,
+        }
+    }
+
+    pub fn translate(self, x: T, y: T, z: T) -> Transform<T> {
+        self.then(transform::new_translation(x, y, z))
+    }
+
+    pub fn scale(self, x: T, y: T, z: T) -> Transform<T> {
+        self.then(transform::new_scale(x, y, z))
+    }
+
+    pub fn rotate_x(self, rad: T) -> Transform<T> {
+        self.then(transform::new_rotation_x(rad))
+    }
+
+    pub fn rotate_y(self, rad: T) -> Transform<T> {
+        self.then(transform::new_rotation_y(rad))
+    }
+
+    pub fn rotate_z(self, rad: T) -> Transform<T> {
+        self.then(transform::new_rotation_z(rad))
+    }
+
+    // None when the axis is too short to have a direction
+    pub fn rotate_axis(self, axis: &Vector3<T>, rad: T) -> Option<Transform<T>> {
+        transform::new_rotation_axis(axis, rad).map(|matrix| self.then(matrix))
+    }
+
+    pub fn sheer(self, xpy: T, xpz: T, ypx: T, ypz: T, zpx: T, zpy: T) -> Transform<T> {
+        self.then(transform::new_sheer(xpy, xpz, ypx, ypz, zpx, zpy))
+    }
+
+    pub fn build(&self) -> Matrix4<T> {
+        self.matrix
+    }
+}
+
+// quaternions are f64 only
+impl Transform {
+    pub fn rotate(self, rotation: &Quaternion) -> Transform {
+        self.then(rotation.to_matrix())
+    }
+}
+
+impl<T: Scalar> Default for Transform<T> {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl<T: Scalar> PartialEq for Transform<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.matrix == other.matrix
+    }
+}
+
+impl<T: Scalar> From<Transform<T>> for Matrix4<T> {
+    fn from(transform: Transform<T>) -> Self {
+        transform.matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::super::super::point::Point3;
+    use super::*;
+
+    #[test]
+    fn identity() {
+        assert_eq!(
+            Transform::<f64>::identity().build(),
+            Matrix4::new_identity()
+        );
+        assert_eq!(Transform::<f64>::default(), Transform::identity());
+    }
+
+    #[test]
+    fn single_step_matches_constructor() {
+        let result = Transform::identity().translate(5.0, -3.0, 2.0).build();
+        assert_eq!(result, transform::new_translation(5.0, -3.0, 2.0));
+    }
+
+    #[test]
+    fn chain_applies_in_order() {
+        let point = Point3::new(1.0, 0.0, 1.0);
+        let transform = Transform::identity()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0)
+            .build();
+
+        assert_eq!(transform * point, Point3::new(15.0, 0.0, 7.0));
+    }
+
+    #[test]
+    fn chain_matches_manual_product() {
+        let manual = transform::new_translation(10.0, 5.0, 7.0)
+            * transform::new_scale(5.0, 5.0, 5.0)
+            * transform::new_rotation_x(PI / 2.0);
+        let built = Transform::identity()
+            .rotate_x(PI / 2.0)
+            .scale(5.0, 5.0, 5.0)
+            .translate(10.0, 5.0, 7.0);
+
+        assert_eq!(Matrix4::from(built), manual);
+    }
+
+    #[test]
+    fn all_steps() {
+        let built = Transform::identity()
+            .sheer(1.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+            .rotate_y(PI / 4.0)
+            .rotate_z(PI / 3.0)
+            .build();
+        let manual = transform::new_rotation_z(PI / 3.0)
+            * transform::new_rotation_y(PI / 4.0)
+            * transform::new_sheer(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+
+        assert_eq!(built, manual);
+    }
+
+    #[test]
+    fn rotate_axis_and_quaternion() {
+        let axis = Vector3::new(1.0, -2.0, 0.5);
+        let by_axis = Transform::identity()
+            .rotate_axis(&axis, 0.8)
+            .expect("axis is not zero")
+            .build();
+        let by_quaternion = Transform::identity()
+            .rotate(&Quaternion::from_axis_angle(&axis, 0.8).expect("axis is not zero"))
+            .build();
+
+        assert_eq!(by_axis, by_quaternion);
+    }
+
+    #[test]
+    fn rotate_axis_zero_length() {
+        assert!(Transform::identity()
+            .rotate_axis(&Vector3::new_zero(), 0.5)
+            .is_none());
+    }
+
+    #[test]
+    fn single_precision() {
+        let built = Transform::<f32>::identity()
+            .rotate_axis(&Vector3::new(0.0, 1.0, 0.0), std::f32::consts::PI / 4.0)
+            .expect("axis is not zero")
+            .scale(2.0, 2.0, 2.0)
+            .translate(1.0, 0.0, 0.0);
+        let manual = transform::new_translation(1.0_f32, 0.0, 0.0)
+            * transform::new_scale(2.0, 2.0, 2.0)
+            * transform::new_rotation_y(std::f32::consts::PI / 4.0);
+
+        assert_eq!(Matrix4::from(built), manual);
+    }
+}
@@ -0,0 +1,190 @@
+use super::{
+    super::{compare, matrix4::Matrix4, quaternion::Quaternion, vector::Vector3},
+    transform,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Decomposition {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+}
+
+impl Decomposition {
+    pub fn compose(&self) -> Matrix4 {
+        transform::new_translation(self.translation.x, self.translation.y, self.translation.z)
+            * self.rotation.to_matrix()
+            * transform::new_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+
+impl Matrix4 {
+    // splits an affine matrix into translation * rotation * scale. None when
+    // the bottom row is not 0 0 0 1, an axis is scaled to nothing, or the
+    // axes aren't perpendicular, since sheer has no place in the result
+    pub fn decompose(&self) -> Option<Decomposition> {
+        let m = &self.data;
+        let bottom = [0.0, 0.0, 0.0, 1.0];
+        if !m[3].iter().zip(bottom).all(|(a, b)| compare::equal(*a, b)) {
+            return None;
+        }
+
+        let translation = Vector3::new(m[0][3], m[1][3], m[2][3]);
+        let mut columns = [0, 1, 2].map(|x| Vector3::new(m[0][x], m[1][x], m[2][x]));
+        let mut scale = columns.map(|column| column.magnitude());
+
+        // checked relative to the column lengths, so small uniform scales
+        // still decompose and only a basis squashed flat is rejected
+        let determinant = columns[0].dot(&columns[1].cross(&columns[2]));
+        if compare::near_singular(determinant, scale.into_iter(), compare::SINGULAR_EPSILON) {
+            return None;
+        }
+
+        // a mirrored basis can't be a rotation, so fold the flip into scale
+        if determinant < 0.0 {
+            scale[0] = -scale[0];
+        }
+
+        for (column, scale) in columns.iter_mut().zip(scale) {
+            *column /= scale;
+        }
+
+        // the columns are unit length now, so this doesn't depend on scale
+        let perpendicular = |a: usize, b: usize| compare::equal(columns[a].dot(&columns[b]), 0.0);
+        if !(perpendicular(0, 1) && perpendicular(0, 2) && perpendicular(1, 2)) {
+            return None;
+        }
+
+        let rotation = Quaternion::from_matrix(&Matrix4::new([
+            [columns[0].x, columns[1].x, columns[2].x, 0.0],
+            [columns[0].y, columns[1].y, columns[2].y, 0.0],
+            [columns[0].z, columns[1].z, columns[2].z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ]));
+
+        Some(Decomposition {
+            translation,
+            rotation,
+            scale: Vector3::new(scale[0], scale[1], scale[2]),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::super::{
+        super::compare::{ApproxEq, Tolerance},
+        builder::Transform,
+    };
+    use super::*;
+
+    #[test]
+    fn decompose_identity() {
+        let result = Matrix4::new_identity()
+            .decompose()
+            .expect("identity is affine");
+
+        assert_eq!(result.translation, Vector3::new_zero());
+        assert_eq!(result.rotation, Quaternion::new_identity());
+        assert_eq!(result.scale, Vector3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn decompose_parts() {
+        let rotation = Quaternion::from_euler(0.3, 1.1, -0.4);
+        let matrix = Transform::identity()
+            .scale(2.0, 3.0, 0.5)
+            .rotate(&rotation)
+            .translate(4.0, -5.0, 6.0)
+            .build();
+
+        let result = matrix.decompose().expect("matrix is affine");
+        assert_eq!(result.translation, Vector3::new(4.0, -5.0, 6.0));
+        assert_eq!(result.rotation, rotation);
+        assert_eq!(result.scale, Vector3::new(2.0, 3.0, 0.5));
+        assert_eq!(result.compose(), matrix);
+    }
+
+    #[test]
+    fn decompose_reflection() {
+        let matrix = Transform::identity()
+            .scale(-1.0, 2.0, 2.0)
+            .rotate_y(PI / 6.0)
+            .build();
+
+        let result = matrix.decompose().expect("matrix is affine");
+        assert_eq!(result.scale, Vector3::new(-1.0, 2.0, 2.0));
+        assert_eq!(result.compose(), matrix);
+    }
+
+    #[test]
+    fn decompose_small_scale() {
+        let rotation = Quaternion::from_euler(0.2, -0.7, 1.3);
+        let matrix = Transform::identity()
+            .scale(1e-5, 1e-5, 1e-5)
+            .rotate(&rotation)
+            .translate(1.0, 2.0, 3.0)
+            .build();
+
+        let result = matrix.decompose().expect("matrix is affine");
+        let tolerance = Tolerance::Relative {
+            absolute: 0.0,
+            relative: 1e-9,
+        };
+        assert!(result
+            .scale
+            .approx_eq(&Vector3::new(1e-5, 1e-5, 1e-5), tolerance));
+        assert_eq!(result.rotation, rotation);
+        assert_eq!(result.translation, Vector3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn decompose_not_affine() {
+        let mut matrix = Matrix4::new_identity();
+        matrix.data[3][0] = 0.5;
+        assert!(matrix.decompose().is_none());
+
+        let flat = transform::new_scale(1.0, 0.0, 1.0);
+        assert!(flat.decompose().is_none());
+    }
+
+    #[test]
+    fn decompose_sheered() {
+        let matrix = Transform::identity()
+            .sheer(0.5, 0.0, 0.0, 0.0, 0.0, 0.0)
+            .scale(2.0, 2.0, 2.0)
+            .rotate_z(PI / 3.0)
+            .build();
+        assert!(matrix.decompose().is_none());
+
+        // non uniform scale after a rotation sheers the basis too
+        let matrix = Transform::identity()
+            .rotate_z(PI / 4.0)
+            .scale(1.0, 3.0, 1.0)
+            .build();
+        assert!(matrix.decompose().is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let decomposition = Decomposition {
+            translation: Vector3::new(1.0, 2.0, 3.0),
+            rotation: Quaternion::new_identity(),
+            scale: Vector3::new(2.0, 2.0, 0.5),
+        };
+
+        let json = serde_json::to_string(&decomposition).unwrap();
+        assert_eq!(
+            json,
+            r#"{"translation":[1.0,2.0,3.0],"rotation":[1.0,0.0,0.0,0.0],"scale":[2.0,2.0,0.5]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Decomposition>(&json).unwrap(),
+            decomposition
+        );
+    }
+}
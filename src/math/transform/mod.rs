@@ -1 +1,3 @@
+pub mod builder;
+pub mod decompose;
 pub mod transform;
@@ -1,126 +1,107 @@
-use std::ops::Neg;
-
-use super::super::matrix::Matrix;
+use super::super::{matrix4::Matrix4, scalar::Scalar, vector::Vector3};
+
+pub fn new_sheer<T: Scalar>(xpy: T, xpz: T, ypx: T, ypz: T, zpx: T, zpy: T) -> Matrix4<T> {
+    Matrix4::new([
+        [T::ONE, xpy, xpz, T::ZERO],
+        [ypx, T::ONE, ypz, T::ZERO],
+        [zpx, zpy, T::ONE, T::ZERO],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
+}
 
-pub fn new_sheer(xpy: f64, xpz: f64, ypx: f64, ypz: f64, zpx: f64, zpy: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            1.0, xpy, xpz, 0.0, ypx, 1.0, ypz, 0.0, zpx, zpy, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
-        ],
-    )
+pub fn new_translation<T: Scalar>(x: T, y: T, z: T) -> Matrix4<T> {
+    Matrix4::new([
+        [T::ONE, T::ZERO, T::ZERO, x],
+        [T::ZERO, T::ONE, T::ZERO, y],
+        [T::ZERO, T::ZERO, T::ONE, z],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
 }
 
-pub fn new_translation(x: f64, y: f64, z: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            1.0, 0.0, 0.0, x, 0.0, 1.0, 0.0, y, 0.0, 0.0, 1.0, z, 0.0, 0.0, 0.0, 1.0,
-        ],
-    )
+pub fn new_scale<T: Scalar>(x: T, y: T, z: T) -> Matrix4<T> {
+    Matrix4::new([
+        [x, T::ZERO, T::ZERO, T::ZERO],
+        [T::ZERO, y, T::ZERO, T::ZERO],
+        [T::ZERO, T::ZERO, z, T::ZERO],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
 }
 
-pub fn new_scale(x: f64, y: f64, z: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            x, 0.0, 0.0, 0.0, 0.0, y, 0.0, 0.0, 0.0, 0.0, z, 0.0, 0.0, 0.0, 0.0, 1.0,
-        ],
-    )
+pub fn new_rotation_x<T: Scalar>(rad: T) -> Matrix4<T> {
+    Matrix4::new([
+        [T::ONE, T::ZERO, T::ZERO, T::ZERO],
+        [T::ZERO, rad.cos(), rad.sin().neg(), T::ZERO],
+        [T::ZERO, rad.sin(), rad.cos(), T::ZERO],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
 }
 
-pub fn new_rotation_x(rad: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            1.0,
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            rad.cos(),
-            rad.sin() * -1.0,
-            0.0,
-            0.0,
-            rad.sin(),
-            rad.cos(),
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            1.0,
-        ],
-    )
+pub fn new_rotation_y<T: Scalar>(rad: T) -> Matrix4<T> {
+    Matrix4::new([
+        [rad.cos(), T::ZERO, rad.sin(), T::ZERO],
+        [T::ZERO, T::ONE, T::ZERO, T::ZERO],
+        [rad.sin().neg(), T::ZERO, rad.cos(), T::ZERO],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
 }
 
-pub fn new_rotation_y(rad: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            rad.cos(),
-            0.0,
-            rad.sin(),
-            0.0,
-            0.0,
-            1.0,
-            0.0,
-            0.0,
-            rad.sin().neg(),
-            0.0,
-            rad.cos(),
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            1.0,
-        ],
-    )
+pub fn new_rotation_z<T: Scalar>(rad: T) -> Matrix4<T> {
+    Matrix4::new([
+        [rad.cos(), rad.sin().neg(), T::ZERO, T::ZERO],
+        [rad.sin(), rad.cos(), T::ZERO, T::ZERO],
+        [T::ZERO, T::ZERO, T::ONE, T::ZERO],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ])
 }
 
-pub fn new_rotation_z(rad: f64) -> Matrix {
-    Matrix::new(
-        4,
-        4,
-        vec![
-            rad.cos(),
-            rad.sin().neg(),
-            0.0,
-            0.0,
-            rad.sin(),
-            rad.cos(),
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            1.0,
-            0.0,
-            0.0,
-            0.0,
-            0.0,
-            1.0,
+// right handed rotation about any axis through the origin, the axis does
+// not need to be normalized. None when it is too short to have a direction
+pub fn new_rotation_axis<T: Scalar>(axis: &Vector3<T>, rad: T) -> Option<Matrix4<T>> {
+    let Vector3 { x, y, z } = axis.normalize();
+    if !(x.is_finite() && y.is_finite() && z.is_finite()) {
+        return None;
+    }
+
+    let (sin, cos) = rad.sin_cos();
+    let t = T::ONE - cos;
+
+    Some(Matrix4::new([
+        [
+            t * x * x + cos,
+            t * x * y - sin * z,
+            t * x * z + sin * y,
+            T::ZERO,
+        ],
+        [
+            t * x * y + sin * z,
+            t * y * y + cos,
+            t * y * z - sin * x,
+            T::ZERO,
         ],
-    )
+        [
+            t * x * z - sin * y,
+            t * y * z + sin * x,
+            t * z * z + cos,
+            T::ZERO,
+        ],
+        [T::ZERO, T::ZERO, T::ZERO, T::ONE],
+    ]))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::f64::consts::PI;
+    use std::{f64::consts::PI, ops::Neg};
 
-    use super::super::super::tuple::Tuple;
+    use super::super::super::{point::Point3, vector::Vector3};
     use super::*;
 
     #[test]
     fn transform_translation() {
         let translation = new_translation(5.0, -3.0, 2.0);
-        let point = Tuple::new_point(-3.0, 4.0, 5.0);
+        let point = Point3::new(-3.0, 4.0, 5.0);
 
-        let result = &translation * point;
-        let expected = Tuple::new_point(2.0, 1.0, 7.0);
+        let result = translation * point;
+        let expected = Point3::new(2.0, 1.0, 7.0);
 
         assert!(result.equals(&expected));
     }
@@ -129,10 +110,10 @@ mod tests {
     fn transform_reverse_translation() {
         let translation = new_translation(5.0, -3.0, 2.0);
         let translation_inverse = translation.inverse().expect("inverse did not work");
-        let point = Tuple::new_point(-3.0, 4.0, 5.0);
+        let point = Point3::new(-3.0, 4.0, 5.0);
 
-        let result = &translation_inverse * point;
-        let expected = Tuple::new_point(-8.0, 7.0, 3.0);
+        let result = translation_inverse * point;
+        let expected = Point3::new(-8.0, 7.0, 3.0);
 
         assert!(result.equals(&expected));
     }
@@ -140,10 +121,10 @@ mod tests {
     #[test]
     fn transform_vector_translation() {
         let translation = new_translation(5.0, -3.0, 2.0);
-        let vector = Tuple::new_vector(-3.0, 4.0, 5.0);
+        let vector = Vector3::new(-3.0, 4.0, 5.0);
 
-        let result = &translation * vector;
-        let expected = Tuple::new_vector(-3.0, 4.0, 5.0);
+        let result = translation * vector;
+        let expected = Vector3::new(-3.0, 4.0, 5.0);
 
         assert!(result.equals(&expected));
     }
@@ -151,9 +132,9 @@ mod tests {
     #[test]
     fn transform_scale() {
         let scale = new_scale(2.0, 3.0, 4.0);
-        let point = Tuple::new_point(-4.0, 6.0, 8.0);
-        let result = &scale * point;
-        let expected = Tuple::new_point(-8.0, 18.0, 32.0);
+        let point = Point3::new(-4.0, 6.0, 8.0);
+        let result = scale * point;
+        let expected = Point3::new(-8.0, 18.0, 32.0);
 
         assert!(result.equals(&expected));
     }
@@ -162,10 +143,10 @@ mod tests {
     fn transform_reverse_scale() {
         let scale = new_scale(2.0, 3.0, 4.0);
         let scale_inserse = scale.inverse().expect("inverse did not work");
-        let point = Tuple::new_point(-4.0, 6.0, 8.0);
+        let point = Point3::new(-4.0, 6.0, 8.0);
 
-        let result = &scale_inserse * (&scale * point);
-        let expected = Tuple::new_point(-4.0, 6.0, 8.0);
+        let result = scale_inserse * (scale * point);
+        let expected = Point3::new(-4.0, 6.0, 8.0);
 
         assert!(result.equals(&expected));
     }
@@ -173,18 +154,18 @@ mod tests {
     #[test]
     fn transform_vector_scale() {
         let scale = new_scale(2.0, 3.0, 4.0);
-        let vector = Tuple::new_vector(-4.0, 6.0, 8.0);
-        let result = &scale * vector;
-        let expected = Tuple::new_vector(-8.0, 18.0, 32.0);
+        let vector = Vector3::new(-4.0, 6.0, 8.0);
+        let result = scale * vector;
+        let expected = Vector3::new(-8.0, 18.0, 32.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_scale_reflection() {
         let reflection = new_scale(-1.0, 1.0, 1.0);
-        let point = Tuple::new_point(-4.0, 6.0, 8.0);
-        let result = &reflection * point;
-        let expected = Tuple::new_point(4.0, 6.0, 8.0);
+        let point = Point3::new(-4.0, 6.0, 8.0);
+        let result = reflection * point;
+        let expected = Point3::new(4.0, 6.0, 8.0);
         assert!(result.equals(&expected));
     }
 
@@ -192,12 +173,11 @@ mod tests {
     fn transform_rotate_x_axis() {
         let rotation_half_quarter = new_rotation_x(PI / 4.0);
         let rotation_full_quarter = new_rotation_x(PI / 2.0);
-        let point = Tuple::new_point(0.0, 1.0, 0.0);
-        let half_quarter = &rotation_half_quarter * point;
-        let expected_half_quarter =
-            Tuple::new_point(0.0, (2.0 as f64).sqrt() / 2.0, (2.0 as f64).sqrt() / 2.0);
-        let full_quarter = &rotation_full_quarter * point;
-        let expected_full_quarter = Tuple::new_point(0.0, 0.0, 1.0);
+        let point = Point3::new(0.0, 1.0, 0.0);
+        let half_quarter = rotation_half_quarter * point;
+        let expected_half_quarter = Point3::new(0.0, 2.0_f64.sqrt() / 2.0, 2.0_f64.sqrt() / 2.0);
+        let full_quarter = rotation_full_quarter * point;
+        let expected_full_quarter = Point3::new(0.0, 0.0, 1.0);
 
         assert!(half_quarter.equals(&expected_half_quarter));
         assert!(full_quarter.equals(&expected_full_quarter));
@@ -207,12 +187,11 @@ mod tests {
     fn transform_rotate_y_axis() {
         let rotation_half_quarter = new_rotation_y(PI / 4.0);
         let rotation_full_quarter = new_rotation_y(PI / 2.0);
-        let point = Tuple::new_point(0.0, 0.0, 1.0);
-        let half_quarter = &rotation_half_quarter * point;
-        let full_quarter = &rotation_full_quarter * point;
-        let expected_half_quarter =
-            Tuple::new_point((2.0 as f64).sqrt() / 2.0, 0.0, (2.0 as f64).sqrt() / 2.0);
-        let expected_full_quarter = Tuple::new_point(1.0, 0.0, 0.0);
+        let point = Point3::new(0.0, 0.0, 1.0);
+        let half_quarter = rotation_half_quarter * point;
+        let full_quarter = rotation_full_quarter * point;
+        let expected_half_quarter = Point3::new(2.0_f64.sqrt() / 2.0, 0.0, 2.0_f64.sqrt() / 2.0);
+        let expected_full_quarter = Point3::new(1.0, 0.0, 0.0);
 
         assert!(half_quarter.equals(&expected_half_quarter));
         assert!(full_quarter.equals(&expected_full_quarter));
@@ -222,15 +201,12 @@ mod tests {
     fn transform_rotate_z_axis() {
         let rotation_half_quarter = new_rotation_z(PI / 4.0);
         let rotation_full_quarter = new_rotation_z(PI / 2.0);
-        let point = Tuple::new_point(0.0, 1.0, 0.0);
-        let half_quarter = &rotation_half_quarter * point;
-        let full_quarter = &rotation_full_quarter * point;
-        let expected_half_quarter = Tuple::new_point(
-            (2.0 as f64).sqrt().neg() / 2.0,
-            (2.0 as f64).sqrt() / 2.0,
-            0.0,
-        );
-        let expected_full_quarter = Tuple::new_point(-1.0, 0.0, 0.0);
+        let point = Point3::new(0.0, 1.0, 0.0);
+        let half_quarter = rotation_half_quarter * point;
+        let full_quarter = rotation_full_quarter * point;
+        let expected_half_quarter =
+            Point3::new(2.0_f64.sqrt().neg() / 2.0, 2.0_f64.sqrt() / 2.0, 0.0);
+        let expected_full_quarter = Point3::new(-1.0, 0.0, 0.0);
 
         assert!(half_quarter.equals(&expected_half_quarter));
         assert!(full_quarter.equals(&expected_full_quarter));
@@ -239,72 +215,72 @@ mod tests {
     #[test]
     fn transform_shear_xpy() {
         let sheer = new_sheer(1.0, 0.0, 0.0, 0.0, 0.0, 0.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(5.0, 3.0, 4.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(5.0, 3.0, 4.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_sheer_xpz() {
         let sheer = new_sheer(0.0, 1.0, 0.0, 0.0, 0.0, 0.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(6.0, 3.0, 4.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(6.0, 3.0, 4.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_sheer_ypx() {
         let sheer = new_sheer(0.0, 0.0, 1.0, 0.0, 0.0, 0.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(2.0, 5.0, 4.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(2.0, 5.0, 4.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_sheer_ypz() {
         let sheer = new_sheer(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(2.0, 7.0, 4.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(2.0, 7.0, 4.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_sheer_zpx() {
         let sheer = new_sheer(0.0, 0.0, 0.0, 0.0, 1.0, 0.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(2.0, 3.0, 6.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(2.0, 3.0, 6.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_sheer_zpy() {
         let sheer = new_sheer(0.0, 0.0, 0.0, 0.0, 0.0, 1.0);
-        let point = Tuple::new_point(2.0, 3.0, 4.0);
-        let result = &sheer * point;
-        let expected = Tuple::new_point(2.0, 3.0, 7.0);
+        let point = Point3::new(2.0, 3.0, 4.0);
+        let result = sheer * point;
+        let expected = Point3::new(2.0, 3.0, 7.0);
         assert!(result.equals(&expected));
     }
 
     #[test]
     fn transform_combo_1() {
-        let point = Tuple::new_point(1.0, 0.0, 1.0);
+        let point = Point3::new(1.0, 0.0, 1.0);
         let rotation = new_rotation_x(PI / 2.0);
         let scale = new_scale(5.0, 5.0, 5.0);
         let translate = new_translation(10.0, 5.0, 7.0);
 
-        let point2 = &rotation * point;
-        let expected_point2 = Tuple::new_point(1.0, -1.0, 0.0);
+        let point2 = rotation * point;
+        let expected_point2 = Point3::new(1.0, -1.0, 0.0);
 
-        let point3 = &scale * point2;
-        let expected_point3 = Tuple::new_point(5.0, -5.0, 0.0);
+        let point3 = scale * point2;
+        let expected_point3 = Point3::new(5.0, -5.0, 0.0);
 
-        let point4 = &translate * point3;
-        let expected_point4 = Tuple::new_point(15.0, 0.0, 7.0);
+        let point4 = translate * point3;
+        let expected_point4 = Point3::new(15.0, 0.0, 7.0);
 
         assert!(point2.equals(&expected_point2));
         assert!(point3.equals(&expected_point3));
@@ -313,16 +289,58 @@ mod tests {
 
     #[test]
     fn transform_combo_2() {
-        let point = Tuple::new_point(1.0, 0.0, 1.0);
+        let point = Point3::new(1.0, 0.0, 1.0);
         let rotate = new_rotation_x(PI / 2.0);
         let scale = new_scale(5.0, 5.0, 5.0);
         let translate = new_translation(10.0, 5.0, 7.0);
 
-        let full_transform = &translate * &(&scale * &rotate);
+        let full_transform = translate * scale * rotate;
 
-        let result = &full_transform * point;
-        let expected = Tuple::new_point(15.0, 0.0, 7.0);
+        let result = full_transform * point;
+        let expected = Point3::new(15.0, 0.0, 7.0);
 
         assert!(result.equals(&expected));
     }
+
+    #[test]
+    fn transform_rotate_axis() {
+        let x_axis =
+            new_rotation_axis(&Vector3::new(2.0, 0.0, 0.0), PI / 3.0).expect("axis is not zero");
+        let y_axis =
+            new_rotation_axis(&Vector3::new(0.0, 1.0, 0.0), PI / 3.0).expect("axis is not zero");
+        let z_axis =
+            new_rotation_axis(&Vector3::new(0.0, 0.0, 1.0), PI / 3.0).expect("axis is not zero");
+
+        assert!(x_axis.equals(&new_rotation_x(PI / 3.0)));
+        assert!(y_axis.equals(&new_rotation_y(PI / 3.0)));
+        assert!(z_axis.equals(&new_rotation_z(PI / 3.0)));
+    }
+
+    #[test]
+    fn transform_rotate_diagonal_axis() {
+        // a third of a turn about (1, 1, 1) cycles the axes
+        let rotation = new_rotation_axis(&Vector3::new(1.0, 1.0, 1.0), 2.0 * PI / 3.0)
+            .expect("axis is not zero");
+        let result = rotation * Point3::new(1.0, 0.0, 0.0);
+        assert!(result.equals(&Point3::new(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn transform_rotate_zero_axis() {
+        assert!(new_rotation_axis(&Vector3::new_zero(), PI / 3.0).is_none());
+        assert!(new_rotation_axis(&Vector3::new(1e-200, 0.0, 0.0), 1.0).is_none());
+        assert!(new_rotation_axis(&Vector3::new(f64::NAN, 0.0, 1.0), 1.0).is_none());
+    }
+
+    #[test]
+    fn transform_single_precision() {
+        let point: Point3<f32> = Point3::new(1.0, 0.0, 1.0);
+        let transform = new_translation(10.0_f32, 5.0, 7.0)
+            * new_scale(5.0, 5.0, 5.0)
+            * new_rotation_x(std::f32::consts::PI / 2.0);
+
+        let result = transform * point;
+        assert!(result.equals(&Point3::new(15.0, 0.0, 7.0)));
+        assert!(result.cast::<f64>().equals(&Point3::new(15.0, 0.0, 7.0)));
+    }
 }
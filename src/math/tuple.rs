@@ -1,16 +1,33 @@
-use super::compare;
+use super::{
+    compare::{self, ApproxEq, Tolerance},
+    ops::{forward_ref_binop, forward_ref_unop},
+    scalar::Scalar,
+};
 
 #[derive(Debug, Clone, Copy)]
-pub struct Tuple {
-    pub x: f64,
-    pub y: f64,
-    pub z: f64,
-    pub w: f64,
+// written as a plain [x, y, z, w] array
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "[T; 4]",
+        into = "[T; 4]",
+        bound(
+            serialize = "T: Scalar + serde::Serialize",
+            deserialize = "T: Scalar + serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Tuple<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
 }
 
-impl std::ops::Add for Tuple {
-    type Output = Tuple;
-    fn add(self, rhs: Tuple) -> Self::Output {
+impl<T: Scalar> std::ops::Add for Tuple<T> {
+    type Output = Tuple<T>;
+    fn add(self, rhs: Tuple<T>) -> Self::Output {
         Tuple {
             x: self.x + rhs.x,
             y: self.y + rhs.y,
@@ -20,8 +37,8 @@ impl std::ops::Add for Tuple {
     }
 }
 
-impl std::ops::AddAssign<Tuple> for Tuple {
-    fn add_assign(&mut self, rhs: Tuple) {
+impl<T: Scalar> std::ops::AddAssign<Tuple<T>> for Tuple<T> {
+    fn add_assign(&mut self, rhs: Tuple<T>) {
         self.x += rhs.x;
         self.y += rhs.y;
         self.z += rhs.z;
@@ -29,9 +46,9 @@ impl std::ops::AddAssign<Tuple> for Tuple {
     }
 }
 
-impl std::ops::Sub for Tuple {
-    type Output = Tuple;
-    fn sub(self, rhs: Tuple) -> Self::Output {
+impl<T: Scalar> std::ops::Sub for Tuple<T> {
+    type Output = Tuple<T>;
+    fn sub(self, rhs: Tuple<T>) -> Self::Output {
         Tuple {
             x: self.x - rhs.x,
             y: self.y - rhs.y,
@@ -41,9 +58,18 @@ impl std::ops::Sub for Tuple {
     }
 }
 
-impl std::ops::Mul for Tuple {
-    type Output = Tuple;
-    fn mul(self, rhs: Tuple) -> Self::Output {
+impl<T: Scalar> std::ops::SubAssign<Tuple<T>> for Tuple<T> {
+    fn sub_assign(&mut self, rhs: Tuple<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+        self.w -= rhs.w;
+    }
+}
+
+impl<T: Scalar> std::ops::Mul for Tuple<T> {
+    type Output = Tuple<T>;
+    fn mul(self, rhs: Tuple<T>) -> Self::Output {
         Tuple {
             x: self.x * rhs.x,
             y: self.y * rhs.y,
@@ -53,8 +79,20 @@ impl std::ops::Mul for Tuple {
     }
 }
 
-impl std::ops::MulAssign<f64> for Tuple {
-    fn mul_assign(&mut self, rhs: f64) {
+impl<T: Scalar> std::ops::Mul<T> for Tuple<T> {
+    type Output = Tuple<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Tuple {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+            w: self.w * rhs,
+        }
+    }
+}
+
+impl<T: Scalar> std::ops::MulAssign<T> for Tuple<T> {
+    fn mul_assign(&mut self, rhs: T) {
         self.x *= rhs;
         self.y *= rhs;
         self.z *= rhs;
@@ -62,8 +100,8 @@ impl std::ops::MulAssign<f64> for Tuple {
     }
 }
 
-impl std::ops::MulAssign<Tuple> for Tuple {
-    fn mul_assign(&mut self, rhs: Tuple) {
+impl<T: Scalar> std::ops::MulAssign<Tuple<T>> for Tuple<T> {
+    fn mul_assign(&mut self, rhs: Tuple<T>) {
         self.x *= rhs.x;
         self.y *= rhs.y;
         self.z *= rhs.z;
@@ -71,8 +109,20 @@ impl std::ops::MulAssign<Tuple> for Tuple {
     }
 }
 
-impl std::ops::DivAssign<f64> for Tuple {
-    fn div_assign(&mut self, rhs: f64) {
+impl<T: Scalar> std::ops::Div<T> for Tuple<T> {
+    type Output = Tuple<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Tuple {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+            w: self.w / rhs,
+        }
+    }
+}
+
+impl<T: Scalar> std::ops::DivAssign<T> for Tuple<T> {
+    fn div_assign(&mut self, rhs: T) {
         self.x /= rhs;
         self.y /= rhs;
         self.z /= rhs;
@@ -80,44 +130,109 @@ impl std::ops::DivAssign<f64> for Tuple {
     }
 }
 
-impl std::ops::Neg for Tuple {
-    type Output = Tuple;
+impl<T: Scalar> std::ops::Neg for Tuple<T> {
+    type Output = Tuple<T>;
     fn neg(self) -> Self::Output {
         Tuple {
-            x: self.x * -1.0,
-            y: self.y * -1.0,
-            z: self.z * -1.0,
-            w: self.w * -1.0,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+            w: -self.w,
+        }
+    }
+}
+
+forward_ref_binop!(impl Add, add for Tuple<T>, Tuple<T>);
+forward_ref_binop!(impl Sub, sub for Tuple<T>, Tuple<T>);
+forward_ref_binop!(impl Mul, mul for Tuple<T>, Tuple<T>);
+forward_ref_binop!(impl Mul, mul for Tuple<T>, T);
+forward_ref_binop!(impl Div, div for Tuple<T>, T);
+forward_ref_unop!(impl Neg, neg for Tuple<T>);
+
+impl<T: Scalar> std::ops::Index<usize> for Tuple<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            3 => &self.w,
+            _ => panic!("tuple index {} out of range", index),
+        }
+    }
+}
+
+impl<T: Scalar> std::ops::IndexMut<usize> for Tuple<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            3 => &mut self.w,
+            _ => panic!("tuple index {} out of range", index),
         }
     }
 }
 
-impl Tuple {
-    pub fn new_vector(x: f64, y: f64, z: f64) -> Tuple {
-        Tuple { x, y, z, w: 0.0 }
+impl<T: Scalar> ApproxEq for Tuple<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.equal(self.x, other.x)
+            && tolerance.equal(self.y, other.y)
+            && tolerance.equal(self.z, other.z)
+            && tolerance.equal(self.w, other.w)
+    }
+}
+
+impl<T: Scalar> PartialEq for Tuple<T> {
+    fn eq(&self, other: &Tuple<T>) -> bool {
+        self.equals(other)
     }
+}
 
-    pub fn new_point(x: f64, y: f64, z: f64) -> Tuple {
-        Tuple { x, y, z, w: 1.0 }
+impl<T: Scalar> From<[T; 4]> for Tuple<T> {
+    fn from([x, y, z, w]: [T; 4]) -> Self {
+        Tuple::new_tuple(x, y, z, w)
     }
+}
 
-    pub fn new_tuple(x: f64, y: f64, z: f64, w: f64) -> Tuple {
+impl<T: Scalar> From<Tuple<T>> for [T; 4] {
+    fn from(tuple: Tuple<T>) -> Self {
+        tuple.to_array()
+    }
+}
+
+impl<T: Scalar> Tuple<T> {
+    pub fn new_vector(x: T, y: T, z: T) -> Tuple<T> {
+        Tuple {
+            x,
+            y,
+            z,
+            w: T::ZERO,
+        }
+    }
+
+    pub fn new_point(x: T, y: T, z: T) -> Tuple<T> {
+        Tuple { x, y, z, w: T::ONE }
+    }
+
+    pub fn new_tuple(x: T, y: T, z: T, w: T) -> Tuple<T> {
         Tuple { x, y, z, w }
     }
 
     pub fn is_vector(&self) -> bool {
-        compare::equal(self.w, 0.0)
+        compare::equal(self.w.to_f64(), 0.0)
     }
 
     pub fn is_point(&self) -> bool {
-        compare::equal(self.w, 1.0)
+        compare::equal(self.w.to_f64(), 1.0)
     }
 
-    pub fn magnitude(&self) -> f64 {
-        f64::sqrt(self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w)
+    pub fn magnitude(&self) -> T {
+        self.dot(self).sqrt()
     }
 
-    pub fn normalize(&self) -> Tuple {
+    pub fn normalize(&self) -> Tuple<T> {
         let magnitude = self.magnitude();
         Tuple {
             x: self.x / magnitude,
@@ -127,23 +242,29 @@ impl Tuple {
         }
     }
 
-    pub fn dot(&self, other: &Tuple) -> f64 {
-        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    pub fn dot(&self, other: &Tuple<T>) -> T {
+        T::dot4(&self.to_array(), &other.to_array())
     }
 
-    pub fn cross(&self, other: &Tuple) -> Tuple {
-        Tuple::new_vector(
-            self.y * other.z - self.z * other.y,
-            self.z * other.x - self.x * other.z,
-            self.x * other.y - self.y * other.x,
-        )
+    pub fn cross(&self, other: &Tuple<T>) -> Tuple<T> {
+        Tuple::from(T::cross3(&self.to_array(), &other.to_array()))
+    }
+
+    pub(crate) fn to_array(self) -> [T; 4] {
+        [self.x, self.y, self.z, self.w]
+    }
+
+    pub fn cast<U: Scalar>(&self) -> Tuple<U> {
+        Tuple {
+            x: U::from_f64(self.x.to_f64()),
+            y: U::from_f64(self.y.to_f64()),
+            z: U::from_f64(self.z.to_f64()),
+            w: U::from_f64(self.w.to_f64()),
+        }
     }
 
-    pub fn equals(&self, other: &Tuple) -> bool {
-        compare::equal(self.x, other.x)
-            && compare::equal(self.y, other.y)
-            && compare::equal(self.z, other.z)
-            && compare::equal(self.w, other.w)
+    pub fn equals(&self, other: &Tuple<T>) -> bool {
+        self.approx_eq(other, Tolerance::default())
     }
 }
 
@@ -157,8 +278,8 @@ mod tests {
         assert_eq!(x.x, 4.3);
         assert_eq!(x.y, -4.2);
         assert_eq!(x.z, 3.1);
-        assert_eq!(x.is_vector(), false);
-        assert_eq!(x.is_point(), true);
+        assert!(!x.is_vector());
+        assert!(x.is_point());
     }
 
     #[test]
@@ -167,22 +288,22 @@ mod tests {
         assert_eq!(x.x, 4.3);
         assert_eq!(x.y, -4.2);
         assert_eq!(x.z, 3.1);
-        assert_eq!(x.is_vector(), true);
-        assert_eq!(x.is_point(), false);
+        assert!(x.is_vector());
+        assert!(!x.is_point());
     }
 
     #[test]
     fn compare_tuples_equal() {
         let x = Tuple::new_point(5.1, 3.4, -1.4);
         let y = Tuple::new_point(5.1, 3.4, -1.4);
-        assert_eq!(x.equals(&y), true);
+        assert!(x.equals(&y));
     }
 
     #[test]
     fn compare_tuples_not_equal() {
         let x = Tuple::new_point(5.2, 3.4, -1.4);
         let y = Tuple::new_point(5.1, 3.4, -1.4);
-        assert_eq!(x.equals(&y), false);
+        assert!(!x.equals(&y));
     }
 
     #[test]
@@ -191,7 +312,7 @@ mod tests {
         let y = Tuple::new_point(-2.0, 3.0, 1.0);
         let result = x + y;
         let expected = Tuple::new_tuple(1.0, 1.0, 6.0, 1.0);
-        assert_eq!(result.equals(&expected), true);
+        assert!(result.equals(&expected));
     }
 
     #[test]
@@ -200,7 +321,7 @@ mod tests {
         let y = Tuple::new_vector(5.0, 6.0, 7.0);
         let result = x - y;
         let expected = Tuple::new_point(-2.0, -4.0, -6.0);
-        assert_eq!(result.equals(&expected), true);
+        assert!(result.equals(&expected));
     }
 
     #[test]
@@ -209,14 +330,14 @@ mod tests {
         let y = Tuple::new_vector(5.0, 6.0, 7.0);
         let result = x - y;
         let expected = Tuple::new_vector(-2.0, -4.0, -6.0);
-        assert_eq!(result.equals(&expected), true);
+        assert!(result.equals(&expected));
     }
 
     #[test]
     fn negate_tuple() {
         let x = -Tuple::new_tuple(1.0, -2.0, 3.0, -4.0);
         let expect = Tuple::new_tuple(-1.0, 2.0, -3.0, 4.0);
-        assert_eq!(x.equals(&expect), true);
+        assert!(x.equals(&expect));
     }
 
     #[test]
@@ -224,7 +345,7 @@ mod tests {
         let mut x = Tuple::new_tuple(1.0, -2.0, 3.0, -4.0);
         x *= 3.5;
         let expect = Tuple::new_tuple(3.5, -7.0, 10.5, -14.0);
-        assert_eq!(x.equals(&expect), true);
+        assert!(x.equals(&expect));
     }
 
     #[test]
@@ -232,7 +353,7 @@ mod tests {
         let mut x = Tuple::new_tuple(1.0, -2.0, 3.0, -4.0);
         x /= 2.0;
         let expect = Tuple::new_tuple(0.5, -1.0, 1.5, -2.0);
-        assert_eq!(x.equals(&expect), true);
+        assert!(x.equals(&expect));
     }
 
     #[test]
@@ -269,7 +390,7 @@ mod tests {
     fn normalize_1() {
         let x = Tuple::new_vector(4.0, 0.0, 0.0);
         let expect = Tuple::new_vector(1.0, 0.0, 0.0);
-        assert_eq!(x.normalize().equals(&expect), true);
+        assert!(x.normalize().equals(&expect));
     }
 
     #[test]
@@ -277,13 +398,13 @@ mod tests {
         let x = Tuple::new_vector(1.0, 2.0, 3.0);
         let z = f64::sqrt(14.0);
         let expect = Tuple::new_vector(1.0 / z, 2.0 / z, 3.0 / z);
-        assert_eq!(x.normalize().equals(&expect), true);
+        assert!(x.normalize().equals(&expect));
     }
 
     #[test]
     fn normalize_3() {
         let x = Tuple::new_vector(1.0, 2.0, 3.0);
-        assert_eq!(x.normalize().magnitude() - 1.0 < f64::EPSILON, true);
+        assert!((x.normalize().magnitude() - 1.0).abs() < f64::EPSILON);
     }
 
     #[test]
@@ -293,7 +414,7 @@ mod tests {
 
         let result = a.dot(&b);
 
-        assert_eq!(compare::equal(result, 20.0), true);
+        assert!(compare::equal(result, 20.0));
     }
 
     #[test]
@@ -304,7 +425,7 @@ mod tests {
         let ab_result = a.cross(&b);
         let ab_expect = Tuple::new_vector(-1.0, 2.0, -1.0);
 
-        assert_eq!(ab_result.equals(&ab_expect), true);
+        assert!(ab_result.equals(&ab_expect));
     }
 
     #[test]
@@ -315,6 +436,100 @@ mod tests {
         let ba_result = b.cross(&a);
         let ba_expect = Tuple::new_vector(1.0, -2.0, 1.0);
 
-        assert_eq!(ba_result.equals(&ba_expect), true);
+        assert!(ba_result.equals(&ba_expect));
+    }
+
+    #[test]
+    fn scale_and_divide_by_value() {
+        let x = Tuple::new_tuple(1.0, -2.0, 3.0, -4.0);
+        assert_eq!(x * 3.5, Tuple::new_tuple(3.5, -7.0, 10.5, -14.0));
+        assert_eq!(&x / 2.0, Tuple::new_tuple(0.5, -1.0, 1.5, -2.0));
+    }
+
+    #[test]
+    fn reference_operands() {
+        let tuples = [
+            Tuple::new_point(3.0, 2.0, 1.0),
+            Tuple::new_vector(5.0, 6.0, 7.0),
+        ];
+
+        let sum = tuples
+            .iter()
+            .fold(Tuple::new_tuple(0.0, 0.0, 0.0, 0.0), |sum, tuple| {
+                sum + tuple
+            });
+        assert_eq!(sum, Tuple::new_tuple(8.0, 8.0, 8.0, 1.0));
+
+        let negated: Vec<Tuple> = tuples.iter().map(|tuple| -tuple * 2.0).collect();
+        assert_eq!(negated[0], Tuple::new_tuple(-6.0, -4.0, -2.0, -2.0));
+        assert_eq!(negated[1], Tuple::new_vector(-10.0, -12.0, -14.0));
+    }
+
+    #[test]
+    fn subtract_assign() {
+        let mut x = Tuple::new_point(3.0, 2.0, 1.0);
+        x -= Tuple::new_vector(5.0, 6.0, 7.0);
+        assert_eq!(x, Tuple::new_point(-2.0, -4.0, -6.0));
+    }
+
+    #[test]
+    fn index() {
+        let mut x = Tuple::new_tuple(1.0, 2.0, 3.0, 4.0);
+        x[2] = 5.0;
+        assert_eq!(x[0], 1.0);
+        assert_eq!(x[2], 5.0);
+        assert_eq!(x[3], 4.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        let x = Tuple::new_point(1.0, 2.0, 3.0);
+        let _ = x[4];
+    }
+
+    #[test]
+    fn approximate_equality() {
+        let x = Tuple::new_point(1.0, 2.0, 3.0);
+        assert_eq!(x, Tuple::new_point(1.00001, 2.0, 3.0));
+        assert_ne!(x, Tuple::new_point(1.001, 2.0, 3.0));
+    }
+
+    #[test]
+    fn approx_eq_with_tolerance() {
+        let x = Tuple::new_point(1e9, 2.0, 3.0);
+        let y = Tuple::new_point(1e9 + 1.0, 2.0, 3.0);
+        let relative = Tolerance::Relative {
+            absolute: 1e-9,
+            relative: 1e-6,
+        };
+
+        assert_ne!(x, y);
+        assert!(x.approx_eq(&y, relative));
+        assert!(!x.approx_eq(&Tuple::new_vector(1e9, 2.0, 3.0), relative));
+    }
+
+    #[test]
+    fn single_precision() {
+        let a: Tuple<f32> = Tuple::new_vector(1.0, 2.0, 3.0);
+        let b: Tuple<f32> = Tuple::new_vector(2.0, 3.0, 4.0);
+
+        assert_eq!(a.dot(&b), 20.0_f32);
+        assert_eq!(a.cross(&b), Tuple::new_vector(-1.0, 2.0, -1.0));
+        assert!((a.normalize().magnitude() - 1.0).abs() < f32::EPSILON * 2.0);
+        assert_eq!(a.cast::<f64>(), Tuple::new_vector(1.0, 2.0, 3.0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let tuple = Tuple::new_point(1.5, -2.0, 3.25);
+
+        let json = serde_json::to_string(&tuple).unwrap();
+        assert_eq!(json, "[1.5,-2.0,3.25,1.0]");
+
+        let result: Tuple = serde_json::from_str(&json).unwrap();
+        assert_eq!(result, tuple);
+        assert!(serde_json::from_str::<Tuple>("[1.0,2.0,3.0]").is_err());
     }
 }
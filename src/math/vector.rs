@@ -0,0 +1,261 @@
+use super::{
+    compare::{ApproxEq, Tolerance},
+    scalar::Scalar,
+    tuple::Tuple,
+};
+
+#[derive(Debug, Clone, Copy)]
+// written as a plain [x, y, z] array
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "[T; 3]",
+        into = "[T; 3]",
+        bound(
+            serialize = "T: Scalar + serde::Serialize",
+            deserialize = "T: Scalar + serde::Deserialize<'de>"
+        )
+    )
+)]
+pub struct Vector3<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Scalar> std::ops::Add for Vector3<T> {
+    type Output = Vector3<T>;
+    fn add(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Scalar> std::ops::AddAssign for Vector3<T> {
+    fn add_assign(&mut self, rhs: Vector3<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: Scalar> std::ops::Sub for Vector3<T> {
+    type Output = Vector3<T>;
+    fn sub(self, rhs: Vector3<T>) -> Self::Output {
+        Vector3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Scalar> std::ops::SubAssign for Vector3<T> {
+    fn sub_assign(&mut self, rhs: Vector3<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Scalar> std::ops::Mul<T> for Vector3<T> {
+    type Output = Vector3<T>;
+    fn mul(self, rhs: T) -> Self::Output {
+        Vector3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T: Scalar> std::ops::MulAssign<T> for Vector3<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.x *= rhs;
+        self.y *= rhs;
+        self.z *= rhs;
+    }
+}
+
+impl<T: Scalar> std::ops::Div<T> for Vector3<T> {
+    type Output = Vector3<T>;
+    fn div(self, rhs: T) -> Self::Output {
+        Vector3::new(self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl<T: Scalar> std::ops::DivAssign<T> for Vector3<T> {
+    fn div_assign(&mut self, rhs: T) {
+        self.x /= rhs;
+        self.y /= rhs;
+        self.z /= rhs;
+    }
+}
+
+impl<T: Scalar> std::ops::Neg for Vector3<T> {
+    type Output = Vector3<T>;
+    fn neg(self) -> Self::Output {
+        Vector3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Scalar> From<[T; 3]> for Vector3<T> {
+    fn from([x, y, z]: [T; 3]) -> Self {
+        Vector3::new(x, y, z)
+    }
+}
+
+impl<T: Scalar> From<Vector3<T>> for [T; 3] {
+    fn from(vector: Vector3<T>) -> Self {
+        [vector.x, vector.y, vector.z]
+    }
+}
+
+impl<T: Scalar> From<Vector3<T>> for Tuple<T> {
+    fn from(vector: Vector3<T>) -> Self {
+        Tuple::new_vector(vector.x, vector.y, vector.z)
+    }
+}
+
+// only tuples with a w of zero are vectors, anything else is handed back
+impl<T: Scalar> TryFrom<Tuple<T>> for Vector3<T> {
+    type Error = Tuple<T>;
+
+    fn try_from(tuple: Tuple<T>) -> Result<Self, Self::Error> {
+        if tuple.is_vector() {
+            Ok(Vector3::new(tuple.x, tuple.y, tuple.z))
+        } else {
+            Err(tuple)
+        }
+    }
+}
+
+impl<T: Scalar> ApproxEq for Vector3<T> {
+    fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+        tolerance.equal(self.x, other.x)
+            && tolerance.equal(self.y, other.y)
+            && tolerance.equal(self.z, other.z)
+    }
+}
+
+impl<T: Scalar> PartialEq for Vector3<T> {
+    fn eq(&self, other: &Vector3<T>) -> bool {
+        self.equals(other)
+    }
+}
+
+impl<T: Scalar> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vector3<T> {
+        Vector3 { x, y, z }
+    }
+
+    pub fn new_zero() -> Vector3<T> {
+        Vector3::new(T::ZERO, T::ZERO, T::ZERO)
+    }
+
+    pub fn magnitude(&self) -> T {
+        self.dot(self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Vector3<T> {
+        *self / self.magnitude()
+    }
+
+    pub fn dot(&self, other: &Vector3<T>) -> T {
+        T::dot4(&self.to_array(), &other.to_array())
+    }
+
+    pub fn cross(&self, other: &Vector3<T>) -> Vector3<T> {
+        let [x, y, z, _] = T::cross3(&self.to_array(), &other.to_array());
+        Vector3::new(x, y, z)
+    }
+
+    // padded with a zero w for the Scalar kernels
+    pub(crate) fn to_array(self) -> [T; 4] {
+        [self.x, self.y, self.z, T::ZERO]
+    }
+
+    pub fn cast<U: Scalar>(&self) -> Vector3<U> {
+        Vector3::new(
+            U::from_f64(self.x.to_f64()),
+            U::from_f64(self.y.to_f64()),
+            U::from_f64(self.z.to_f64()),
+        )
+    }
+
+    pub fn equals(&self, other: &Vector3<T>) -> bool {
+        self.approx_eq(other, Tolerance::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::compare;
+    use super::*;
+
+    #[test]
+    fn add_vectors() {
+        let a = Vector3::new(3.0, -2.0, 5.0);
+        let b = Vector3::new(-2.0, 3.0, 1.0);
+        assert!((a + b).equals(&Vector3::new(1.0, 1.0, 6.0)));
+    }
+
+    #[test]
+    fn subtract_vectors() {
+        let a = Vector3::new(3.0, 2.0, 1.0);
+        let b = Vector3::new(5.0, 6.0, 7.0);
+        assert!((a - b).equals(&Vector3::new(-2.0, -4.0, -6.0)));
+    }
+
+    #[test]
+    fn scale_vector() {
+        let a = Vector3::new(1.0, -2.0, 3.0);
+        assert!((a * 3.5).equals(&Vector3::new(3.5, -7.0, 10.5)));
+        assert!((a / 2.0).equals(&Vector3::new(0.5, -1.0, 1.5)));
+        assert!((-a).equals(&Vector3::new(-1.0, 2.0, -3.0)));
+    }
+
+    #[test]
+    fn magnitude_and_normalize() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        assert_eq!(a.magnitude(), f64::sqrt(14.0));
+
+        let z = f64::sqrt(14.0);
+        let expect = Vector3::new(1.0 / z, 2.0 / z, 3.0 / z);
+        assert!(a.normalize().equals(&expect));
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        let a = Vector3::new(1.0, 2.0, 3.0);
+        let b = Vector3::new(2.0, 3.0, 4.0);
+
+        assert!(compare::equal(a.dot(&b), 20.0));
+        assert!(a.cross(&b).equals(&Vector3::new(-1.0, 2.0, -1.0)));
+        assert!(b.cross(&a).equals(&Vector3::new(1.0, -2.0, 1.0)));
+    }
+
+    #[test]
+    fn convert_tuple() {
+        let tuple: Tuple = Vector3::new(1.0, 2.0, 3.0).into();
+        assert!(tuple.equals(&Tuple::new_vector(1.0, 2.0, 3.0)));
+
+        let vector = Vector3::try_from(tuple).expect("tuple is a vector");
+        assert!(vector.equals(&Vector3::new(1.0, 2.0, 3.0)));
+        assert!(Vector3::try_from(Tuple::new_point(1.0, 2.0, 3.0)).is_err());
+    }
+
+    #[test]
+    fn approx_eq_single_precision_ulps() {
+        let tolerance = Tolerance::Ulps {
+            absolute: 0.0,
+            ulps: 1,
+        };
+        let next = f32::from_bits(2.0_f32.to_bits() + 1);
+        let vector = Vector3::new(1.0_f32, 2.0, 3.0);
+        assert!(vector.approx_eq(&Vector3::new(1.0, next, 3.0), tolerance));
+        assert!(!vector.approx_eq(&Vector3::new(1.0, 2.1, 3.0), tolerance));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let vector = Vector3::new(0.5f32, 0.0, -1.0);
+
+        let json = serde_json::to_string(&vector).unwrap();
+        assert_eq!(json, "[0.5,0.0,-1.0]");
+        assert_eq!(serde_json::from_str::<Vector3<f32>>(&json).unwrap(), vector);
+    }
+}